@@ -1,19 +1,533 @@
-use unwrap_or::{unwrap_err_or, unwrap_ok_or};
+use indexmap::IndexMap;
+use unwrap_or::unwrap_err_or;
 
 use crate::{
-    http_data::{HttpData, Names},
-    open_api::OpenApi,
-    utils::{create_file, create_folders},
+    error::AppError,
+    http_data::HttpData,
+    logging::{error_at, get_warnings_count, set_log_format, warn, warn_at, LogFormat},
+    names::{find_file_path_collisions, Names, PathCase, RequestNaming},
+    open_api::{HttpMethod, OpenApi},
+    output::{
+        clean_output, collect_variables, create_banner, create_env_file, create_index,
+        create_shared_variables_file, dedupe_files, get_shared_variables_import, get_top_folder,
+        remove_empty_folders, report_diff, validate_placeholders, write_bundle, GeneratedFile,
+        MARKER_FILE_NAME,
+    },
+    skeleton::ReadOnlyMode,
+    snippet::SnippetLanguage,
+    utils::{append_file, create_file, create_folders, expand_path, join_output_path, run_command},
+    watch::watch,
 };
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     io::Read,
 };
-use std::{env, path::Path};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Separates requests in files which are meant to hold many of them.
+const REQUEST_SEPARATOR: &str = "\n\n###\n\n";
+
+/// HTTP versions accepted by `--http-version`.
+const HTTP_VERSIONS: [&str; 3] = ["1.0", "1.1", "2"];
+
+/// Extensions of the schemas generated with `--schema-dir`.
+const SCHEMA_EXTENSIONS: [&str; 3] = ["json", "yaml", "yml"];
+
+#[derive(Clone)]
+pub struct Config {
+    pub file_path: String,
+    pub output_path: String,
+    /// Omits deprecated parameters & body properties from the generated requests.
+    pub exclude_deprecated: bool,
+    /// Writes files with `\r\n` line endings instead of `\n`.
+    pub crlf: bool,
+    /// Annotates every property of the JSON body skeleton with its type & description.
+    pub json_body_comments: bool,
+    /// Writes requests of every path as soon as they are generated, instead of buffering them.
+    pub stream: bool,
+    /// Reports differences against the output folder instead of writing the files.
+    pub diff: bool,
+    /// Generates only the operation with this operationId and prints it to stdout.
+    pub operation: Option<String>,
+    /// Replaces examples of sensitive fields with variables.
+    pub redact: bool,
+    /// Patterns of field names which are considered sensitive.
+    pub redact_patterns: Vec<String>,
+    /// Writes all the requests into a single file.
+    pub single_file: bool,
+    /// Maximum number of requests in a single file, the rest is split into numbered parts.
+    pub split_size: Option<usize>,
+    /// Emits `Content-Length: 0` for POST, PUT & PATCH requests without a body.
+    pub explicit_empty_body: bool,
+    /// Command which is run for every written file, with the file path as its last argument.
+    pub post_process: Option<String>,
+    /// Writes all the files into a zip archive at this path, instead of the output folder.
+    pub bundle: Option<String>,
+    /// Sorts body properties alphabetically instead of keeping the schema order.
+    pub sort_properties: bool,
+    /// Transliterates or strips non-ASCII characters in file & folder names.
+    pub ascii_only: bool,
+    /// Content of the template used for rendering the requests instead of the built-in layout.
+    pub template: Option<String>,
+    /// Removes the content of the output folder before writing the files.
+    pub clean: bool,
+    /// Cleans the output folder even when it wasn't generated by this tool.
+    pub force: bool,
+    /// Writes `index.md` linking all the generated files into the root of the output folder.
+    pub emit_index: bool,
+    /// Content types of the request body which are preferred over the built-in priority.
+    pub preferred_content_types: Vec<String>,
+    /// Omits authentication headers & security comments from the generated requests.
+    pub no_auth: bool,
+    /// Regenerates the output whenever the schema or any of the referenced files changes.
+    pub watch: bool,
+    /// Prints operations of the schema to stdout instead of generating the files.
+    pub summary_only: bool,
+    /// Headers added to every request, e.g. `Accept-Language: en-US`.
+    pub headers: Vec<String>,
+    /// Adds `# @no-redirect` directive to every request.
+    pub no_redirect: bool,
+    /// Adds `# @no-cookie-jar` directive to every request.
+    pub no_cookie_jar: bool,
+    /// Maximum depth of the folders, deeper path segments are flattened into the file name.
+    pub group_depth: Option<usize>,
+    /// Writes every operation into its own file, instead of grouping operations by path.
+    pub one_file_per_operation: bool,
+    /// Files with content identical to an earlier file only reference that file.
+    pub dedupe_files: bool,
+    /// Reports variables which are used but missing in the env file, and vice versa.
+    pub validate_placeholders: bool,
+    /// Mismatches of `--validate-placeholders` & `--validate-unique-paths` are errors instead of warnings.
+    pub strict: bool,
+    /// Body without an example reuses example of the successful response.
+    pub example_from_response: bool,
+    /// Maximum number of files which can be written, the run is aborted before writing when exceeded.
+    pub max_files: Option<usize>,
+    /// Places every query parameter on its own line.
+    pub multiline_query: bool,
+    /// Name of the property which wraps the content of responses, e.g. `data`.
+    pub unwrap: Option<String>,
+    /// Folder with schemas, each of them is generated into its own subfolder of the output.
+    pub schema_dir: Option<String>,
+    /// Names every request with `# @name` directive, unique within its file.
+    pub request_names: bool,
+    /// Bodies are written into JSON files next to the requests, which reference them.
+    pub body_as_file: bool,
+    /// Every top-level folder gets its own env file with variables of its requests.
+    pub per_folder_env: bool,
+    /// HTTP version appended to every request line, e.g. `1.1`.
+    pub http_version: Option<String>,
+    /// Run fails when any warning was reported.
+    pub fail_on_warnings: bool,
+    /// Language of the client snippets generated next to the request files.
+    pub snippet: Option<SnippetLanguage>,
+    /// Request files start with a banner with title, version, contact & license of the API.
+    pub verbose_banner: bool,
+    /// Paths with a single method are placed into flat files, unless a folder of the same name exists.
+    pub collapse_single_method: bool,
+    /// Scheme word of the Authorization header of bearer schemes, e.g. `Token`.
+    pub auth_scheme: String,
+    /// Requests which are not simple are preceded by their CORS preflight request.
+    pub emit_preflight: bool,
+    /// Case of the file & folder names, the requests keep the original path.
+    pub path_case: PathCase,
+    /// Maximum number of requests in a request file, the rest spills into numbered continuation files.
+    pub max_requests_per_file: Option<usize>,
+    /// Omits security comments, authentication headers are kept.
+    pub no_auth_comments: bool,
+    /// Path parameters are percent-encoded, variables by the HTTP client when the request is sent.
+    pub encode_path_params: bool,
+    /// Every member of oneOf body with discriminator is emitted as its own request.
+    pub expand_variants: bool,
+    /// Adds commented out `Idempotency-Key` header to POST, PUT, PATCH & DELETE requests.
+    pub emit_idempotency_key: bool,
+    /// Removes folders which were created by the run, but were left without any file.
+    pub no_empty_folders: bool,
+    /// Source of the names of single operations, i.e. of their files & `@name` directives.
+    pub request_naming: RequestNaming,
+    /// Requests of all the paths with the same first segment are placed into a single file, e.g. `users.http`.
+    pub group_by_root: bool,
+    /// External examples of the bodies are fetched & inlined, instead of only referencing them.
+    pub fetch_examples: bool,
+    /// Order of the requests of the same path.
+    pub method_order: Vec<HttpMethod>,
+    /// Variables are written into a shared variables file, which is imported at the top of every request file.
+    pub shared_vars_file: bool,
+    /// Equivalent curl command is emitted as a comment above every request.
+    pub emit_curl_comment: bool,
+    /// How readOnly properties are handled in the request bodies.
+    pub read_only_mode: ReadOnlyMode,
+    /// Reports paths which end up in the same file only because of the normalization of their names.
+    pub validate_unique_paths: bool,
+    /// Variables of the server URL are added into the env file with their defaults, HTTP_HOST is the URL using them.
+    pub include_server_variables_as_env: bool,
+    /// Every alternative security requirement of the operation is emitted as its own request.
+    pub auth_alternatives: bool,
+    /// Writes the parsed schema as JSON to this path, instead of generating the files.
+    pub dump_model: Option<String>,
+    /// Seconds after which fetching of an external example is aborted, see `--fetch-examples`.
+    pub fetch_timeout: u64,
+    /// readOnly properties are removed from `required` of the request bodies & component schemas.
+    pub strip_read_only_required: bool,
+}
+
+/// Requests which are placed into the same file.
+struct FileRequests {
+    names: Names,
+    formatted: Vec<String>,
+    request_lines: Vec<String>,
+    snippets: Vec<String>,
+    /// Name & content of the body files, see `--body-as-file`.
+    body_files: Vec<(String, String)>,
+    /// Number of requests deciding placement of the file, see `add_requests_count`.
+    requests_count: usize,
+}
+
+impl Config {
+    /// Parses the command line arguments, without checking whenever schema & folder paths exists.
+    pub fn from_args(args: &Vec<String>) -> Result<Config, AppError> {
+        // paths are expanded before their existence is checked, e.g. `~/apis` or `$API_DIR/schema.json`
+        let schema_dir = get_argument(args, &String::from("schema-dir"), &true)
+            .ok()
+            .map(|schema_dir| expand_path(&schema_dir));
+        let file_path = match get_argument(args, &String::from("schema"), &true) {
+            Ok(file_path) => expand_path(&file_path),
+            // schemas are discovered in the folder
+            Err(_) if schema_dir.is_some() => String::new(),
+            Err(_) => {
+                return Err(AppError::Usage(String::from("Schema path argument is missing!")));
+            }
+        };
+        let operation = get_argument(args, &String::from("operation"), &true).ok();
+        let bundle = get_argument(args, &String::from("bundle"), &true).ok();
+        let summary_only = get_argument(args, &String::from("summary-only"), &false).is_ok();
+        let dump_model = get_argument(args, &String::from("dump-model"), &true).ok();
+        let output_path = match get_argument(args, &String::from("output"), &true) {
+            Ok(output_path) => expand_path(&output_path),
+            // single operation & summary are printed to stdout, bundle is written into a zip archive
+            // and the model into its own file, so output folder is not needed
+            Err(_) if operation.is_some() || bundle.is_some() || summary_only || dump_model.is_some() => {
+                String::new()
+            }
+            Err(_) => {
+                return Err(AppError::Usage(String::from("Output path argument is missing!")));
+            }
+        };
+
+        let exclude_deprecated =
+            get_argument(args, &String::from("exclude-deprecated"), &false).is_ok();
+        let crlf = get_argument(args, &String::from("crlf"), &false).is_ok();
+        let json_body_comments =
+            get_argument(args, &String::from("json-body-comments"), &false).is_ok();
+        let stream = get_argument(args, &String::from("stream"), &false).is_ok();
+        let diff = get_argument(args, &String::from("diff"), &false).is_ok();
+        let redact = get_argument(args, &String::from("redact"), &false).is_ok();
+        let redact_patterns = get_argument(args, &String::from("redact-patterns"), &true)
+            .unwrap_or(String::from("password,token,secret,key"))
+            .split(',')
+            .map(|pattern| pattern.trim().to_owned())
+            .filter(|pattern| pattern.len() > 0)
+            .collect();
+        let split_size = match get_argument(args, &String::from("split-size"), &true) {
+            Ok(size) => match size.parse::<usize>() {
+                Ok(size) if size > 0 => Some(size),
+                _ => {
+                    return Err(AppError::Usage(String::from(
+                        "Split size has to be a positive number!",
+                    )));
+                }
+            },
+            Err(_) => None,
+        };
+        let single_file =
+            split_size.is_some() || get_argument(args, &String::from("single-file"), &false).is_ok();
+        let explicit_empty_body =
+            get_argument(args, &String::from("explicit-empty-body"), &false).is_ok();
+        let post_process = get_argument(args, &String::from("post-process"), &true).ok();
+        let sort_properties =
+            get_argument(args, &String::from("sort-properties"), &false).is_ok();
+        let ascii_only = get_argument(args, &String::from("ascii-only"), &false).is_ok();
+        let template = match get_argument(args, &String::from("template"), &true) {
+            Ok(template_path) => match fs::read_to_string(&template_path) {
+                Ok(template) => Some(template),
+                Err(err) => {
+                    return Err(AppError::Io(format!(
+                        "Template could not be read from {}: {}",
+                        template_path, err
+                    )));
+                }
+            },
+            Err(_) => None,
+        };
+        let clean = get_argument(args, &String::from("clean"), &false).is_ok();
+        let force = get_argument(args, &String::from("force"), &false).is_ok();
+        let emit_index = get_argument(args, &String::from("emit-index"), &false).is_ok();
+        let preferred_content_types =
+            get_argument(args, &String::from("prefer-content-type"), &true)
+                .unwrap_or_default()
+                .split(',')
+                .map(|content_type| content_type.trim().to_lowercase())
+                .filter(|content_type| content_type.len() > 0)
+                .collect();
+        let no_auth = get_argument(args, &String::from("no-auth"), &false).is_ok();
+        let watch = get_argument(args, &String::from("watch"), &false).is_ok();
+        let no_redirect = get_argument(args, &String::from("no-redirect"), &false).is_ok();
+        let no_cookie_jar = get_argument(args, &String::from("no-cookie-jar"), &false).is_ok();
+        let group_depth = match get_argument(args, &String::from("group-depth"), &true) {
+            Ok(depth) => match depth.parse::<usize>() {
+                Ok(depth) => Some(depth),
+                Err(_) => {
+                    return Err(AppError::Usage(String::from(
+                        "Group depth has to be a non-negative number!",
+                    )));
+                }
+            },
+            Err(_) => None,
+        };
+        let one_file_per_operation =
+            get_argument(args, &String::from("one-file-per-operation"), &false).is_ok();
+        let dedupe_files = get_argument(args, &String::from("dedupe-files"), &false).is_ok();
+        let validate_placeholders =
+            get_argument(args, &String::from("validate-placeholders"), &false).is_ok();
+        let strict = get_argument(args, &String::from("strict"), &false).is_ok();
+        let example_from_response =
+            get_argument(args, &String::from("example-from-response"), &false).is_ok();
+        let max_files = match get_argument(args, &String::from("max-files"), &true) {
+            Ok(max_files) => match max_files.parse::<usize>() {
+                Ok(max_files) => Some(max_files),
+                Err(_) => {
+                    return Err(AppError::Usage(String::from(
+                        "Max files has to be a non-negative number!",
+                    )));
+                }
+            },
+            Err(_) => None,
+        };
+        let multiline_query = get_argument(args, &String::from("multiline-query"), &false).is_ok();
+        let unwrap = get_argument(args, &String::from("unwrap"), &true).ok();
+        let request_names = get_argument(args, &String::from("request-names"), &false).is_ok();
+        let body_as_file = get_argument(args, &String::from("body-as-file"), &false).is_ok();
+        let per_folder_env = get_argument(args, &String::from("per-folder-env"), &false).is_ok();
+        let http_version = match get_argument(args, &String::from("http-version"), &true) {
+            Ok(http_version) if HTTP_VERSIONS.contains(&http_version.as_str()) => Some(http_version),
+            Ok(_) => {
+                return Err(AppError::Usage(format!(
+                    "HTTP version has to be one of {}!",
+                    HTTP_VERSIONS.join(", ")
+                )));
+            }
+            Err(_) => None,
+        };
+        let fail_on_warnings =
+            get_argument(args, &String::from("fail-on-warnings"), &false).is_ok();
+        let snippet = match get_argument(args, &String::from("snippet"), &true) {
+            Ok(name) => match SnippetLanguage::from_name(&name) {
+                Some(language) => Some(language),
+                None => {
+                    return Err(AppError::Usage(format!(
+                        "Snippet language has to be one of {}!",
+                        SnippetLanguage::ALL.map(|language| language.get_name()).join(", ")
+                    )));
+                }
+            },
+            Err(_) => None,
+        };
+        let verbose_banner = get_argument(args, &String::from("verbose-banner"), &false).is_ok();
+        let collapse_single_method =
+            get_argument(args, &String::from("collapse-single-method"), &false).is_ok();
+        let auth_scheme = get_argument(args, &String::from("auth-scheme"), &true)
+            .unwrap_or(String::from("Bearer"));
+        let emit_preflight = get_argument(args, &String::from("emit-preflight"), &false).is_ok();
+        let path_case = match get_argument(args, &String::from("path-case"), &true) {
+            Ok(name) => match PathCase::from_name(&name) {
+                Some(path_case) => path_case,
+                None => {
+                    return Err(AppError::Usage(format!(
+                        "Path case has to be one of {}!",
+                        PathCase::ALL.map(|case| case.get_name()).join(", ")
+                    )));
+                }
+            },
+            Err(_) => PathCase::Original,
+        };
+        let max_requests_per_file =
+            match get_argument(args, &String::from("max-requests-per-file"), &true) {
+                Ok(count) => match count.parse::<usize>() {
+                    Ok(count) if count > 0 => Some(count),
+                    _ => {
+                        return Err(AppError::Usage(String::from(
+                            "Maximum number of requests per file has to be a positive number!",
+                        )));
+                    }
+                },
+                Err(_) => None,
+            };
+        let no_auth_comments =
+            get_argument(args, &String::from("no-auth-comments"), &false).is_ok();
+        let encode_path_params =
+            get_argument(args, &String::from("encode-path-params"), &false).is_ok();
+        let expand_variants = get_argument(args, &String::from("expand-variants"), &false).is_ok();
+        let emit_idempotency_key =
+            get_argument(args, &String::from("emit-idempotency-key"), &false).is_ok();
+        let no_empty_folders = get_argument(args, &String::from("no-empty-folders"), &false).is_ok();
+        let request_naming = match get_argument(args, &String::from("request-naming"), &true) {
+            Ok(name) => match RequestNaming::from_name(&name) {
+                Some(request_naming) => request_naming,
+                None => {
+                    return Err(AppError::Usage(format!(
+                        "Request naming has to be one of {}!",
+                        RequestNaming::ALL.map(|naming| naming.get_name()).join(", ")
+                    )));
+                }
+            },
+            Err(_) => RequestNaming::OperationId,
+        };
+        let group_by_root = get_argument(args, &String::from("group-by-root"), &false).is_ok();
+        let fetch_examples = get_argument(args, &String::from("fetch-examples"), &false).is_ok();
+        let fetch_timeout = match get_argument(args, &String::from("fetch-timeout"), &true) {
+            Ok(seconds) => match seconds.parse::<u64>() {
+                Ok(seconds) if seconds > 0 => seconds,
+                _ => {
+                    return Err(AppError::Usage(String::from(
+                        "Fetch timeout has to be a positive number of seconds!",
+                    )));
+                }
+            },
+            Err(_) => 30,
+        };
+        let strip_read_only_required =
+            get_argument(args, &String::from("strip-readonly-required"), &false).is_ok();
+        let method_order = match get_argument(args, &String::from("method-order"), &true) {
+            Ok(order) => parse_method_order(&order)?,
+            Err(_) => Vec::from(HttpMethod::ALL),
+        };
+        let shared_vars_file = get_argument(args, &String::from("shared-vars-file"), &false).is_ok();
+        let emit_curl_comment =
+            get_argument(args, &String::from("emit-curl-comment"), &false).is_ok();
+        let read_only_mode = match get_argument(args, &String::from("readonly-mode"), &true) {
+            Ok(name) => match ReadOnlyMode::from_name(&name) {
+                Some(read_only_mode) => read_only_mode,
+                None => {
+                    return Err(AppError::Usage(format!(
+                        "Readonly mode has to be one of {}!",
+                        ReadOnlyMode::ALL.map(|mode| mode.get_name()).join(", ")
+                    )));
+                }
+            },
+            Err(_) => ReadOnlyMode::Omit,
+        };
+        let validate_unique_paths =
+            get_argument(args, &String::from("validate-unique-paths"), &false).is_ok();
+        let include_server_variables_as_env =
+            get_argument(args, &String::from("include-server-variables-as-env"), &false).is_ok();
+        let auth_alternatives =
+            get_argument(args, &String::from("auth-alternatives"), &false).is_ok();
+        let mut headers = match get_argument(args, &String::from("base-headers-file"), &true) {
+            Ok(path) => read_headers_file(&path)?,
+            Err(_) => Vec::new(),
+        };
+        headers.append(&mut get_arguments(&args, &String::from("header")));
+        if let Some(header) = headers.iter().find(|header| !is_valid_header(header)) {
+            return Err(AppError::Usage(format!(
+                "Header \"{}\" has to be in \"Key: Value\" format!",
+                header
+            )));
+        }
+
+        return Ok(Config {
+            file_path,
+            output_path,
+            exclude_deprecated,
+            crlf,
+            json_body_comments,
+            stream,
+            diff,
+            operation,
+            redact,
+            redact_patterns,
+            single_file,
+            split_size,
+            explicit_empty_body,
+            post_process,
+            bundle,
+            sort_properties,
+            ascii_only,
+            template,
+            clean,
+            force,
+            emit_index,
+            preferred_content_types,
+            no_auth,
+            watch,
+            summary_only,
+            headers,
+            no_redirect,
+            no_cookie_jar,
+            group_depth,
+            one_file_per_operation,
+            dedupe_files,
+            validate_placeholders,
+            strict,
+            example_from_response,
+            max_files,
+            multiline_query,
+            unwrap,
+            schema_dir,
+            request_names,
+            body_as_file,
+            per_folder_env,
+            http_version,
+            fail_on_warnings,
+            snippet,
+            verbose_banner,
+            collapse_single_method,
+            auth_scheme,
+            emit_preflight,
+            path_case,
+            max_requests_per_file,
+            no_auth_comments,
+            encode_path_params,
+            expand_variants,
+            emit_idempotency_key,
+            no_empty_folders,
+            request_naming,
+            group_by_root,
+            fetch_examples,
+            method_order,
+            shared_vars_file,
+            emit_curl_comment,
+            read_only_mode,
+            validate_unique_paths,
+            include_server_variables_as_env,
+            auth_alternatives,
+            dump_model,
+            fetch_timeout,
+            strip_read_only_required,
+        });
+    }
+
+    /// Creates config of the arguments, with placeholder schema & output paths, used by the tests.
+    #[cfg(test)]
+    pub fn from_test_args(args: &[&str]) -> Config {
+        let mut all_args = Vec::from(["open-api-to-http", "--schema", "schema.json", "--output", "out"]);
+        all_args.extend_from_slice(args);
+        let all_args = all_args.iter().map(|arg| arg.to_string()).collect();
+        return Config::from_args(&all_args).unwrap();
+    }
 
-struct Config {
-    file_path: String,
-    output_path: String,
+    /// Checks whenever examples of the field should be redacted,
+    /// i.e. `--redact` is set and the name contains any of the sensitive patterns, ignoring case.
+    pub fn should_redact(&self, name: &String) -> bool {
+        let name = name.to_lowercase();
+        return self.redact
+            && self
+                .redact_patterns
+                .iter()
+                .any(|pattern| name.contains(&pattern.to_lowercase()));
+    }
 }
 
 pub struct Application {
@@ -22,42 +536,239 @@ pub struct Application {
 
 impl Application {
     /// Validates provided parameters, and checks whenever schema & folder paths exists.
-    pub fn prepare() -> Result<Self, exitcode::ExitCode> {
+    pub fn prepare() -> Result<Self, AppError> {
         let args: Vec<String> = env::args().collect();
 
+        // format applies to all the messages, including warnings about the other arguments
+        if let Ok(name) = get_argument(&args, &String::from("log-format"), &true) {
+            match LogFormat::from_name(&name) {
+                Some(log_format) => set_log_format(log_format),
+                None => {
+                    return Err(AppError::Usage(String::from(
+                        "Log format has to be either text or json!",
+                    )));
+                }
+            }
+        }
+
         unwrap_err_or!(get_argument(&args, &String::from("help"), &false), _, {
             println!("Usage:");
             println!("  open-api-to-http --output PATH --schema PATH");
             println!("      generates http files from provided OpenAPI schema.");
-            return Err(exitcode::OK);
+            println!("  open-api-to-http --operation OPERATION_ID --schema PATH");
+            println!("      prints request of a single operation.");
+            println!("  open-api-to-http --output PATH --schema-dir PATH");
+            println!("      generates http files from every schema in the folder, into subfolders named after them.");
+            println!("  open-api-to-http --summary-only --schema PATH");
+            println!("      prints all operations of the schema, without generating the files.");
+            println!("  open-api-to-http --dump-model PATH --schema PATH");
+            println!("      writes the schema as it was understood, i.e. with resolved references, as JSON.");
+            println!("Options:");
+            println!("  --exclude-deprecated");
+            println!("      omits deprecated parameters & body properties from the generated requests.");
+            println!("  --crlf");
+            println!("      writes files with CRLF line endings.");
+            println!("  --json-body-comments");
+            println!("      annotates JSON body properties with trailing // comments.");
+            println!("  --stream");
+            println!("      writes requests as they are generated, reduces memory usage for huge schemas.");
+            println!("  --diff");
+            println!("      reports files which would be changed, created or removed, without writing them.");
+            println!("  --redact");
+            println!("      replaces examples of sensitive fields (password, token, secret, key) with variables.");
+            println!("  --redact-patterns PATTERNS");
+            println!("      comma separated list of sensitive field name patterns used by --redact.");
+            println!("  --single-file");
+            println!("      writes all requests into a single requests.http file.");
+            println!("  --split-size N");
+            println!("      splits the single file into part-001.http, part-002.http, ... with at most N requests.");
+            println!("  --explicit-empty-body");
+            println!("      emits Content-Length: 0 header for POST, PUT & PATCH requests without a body.");
+            println!("  --post-process COMMAND");
+            println!("      runs the command for every written file, passing the file path as the last argument.");
+            println!("  --bundle PATH");
+            println!("      writes all the files into a zip archive instead of the output folder.");
+            println!("  --sort-properties");
+            println!("      sorts body properties alphabetically instead of keeping the schema order.");
+            println!("  --ascii-only");
+            println!("      transliterates or strips non-ASCII characters in file & folder names.");
+            println!("  --template PATH");
+            println!("      renders requests with the template, available variables are");
+            println!("      {{{{method}}}}, {{{{path}}}}, {{{{host}}}}, {{{{headers}}}}, {{{{body}}}} & {{{{comments}}}}.");
+            println!("  --clean");
+            println!("      removes the content of the output folder (except the env file) before writing.");
+            println!("  --force");
+            println!("      allows --clean of output folders which were not generated by this tool.");
+            println!("  --emit-index");
+            println!("      writes index.md linking all the generated files with their requests.");
+            println!("  --prefer-content-type TYPES");
+            println!("      comma separated list of body content types preferred over json > form > multipart > xml.");
+            println!("  --no-auth");
+            println!("      omits authentication headers & security comments from the generated requests.");
+            println!("  --watch");
+            println!("      regenerates the output whenever the schema or referenced files change, until Ctrl-C.");
+            println!("  --header \"KEY: VALUE\"");
+            println!("      adds the header to every request, can be used multiple times.");
+            println!("  --base-headers-file <PATH>");
+            println!("      adds headers from the file (one \"KEY: VALUE\" per line) to every request, before --header ones.");
+            println!("  --no-redirect");
+            println!("      adds # @no-redirect directive to every request.");
+            println!("  --no-cookie-jar");
+            println!("      adds # @no-cookie-jar directive to every request.");
+            println!("  --group-depth N");
+            println!("      nests folders at most N levels deep, the rest of the path is joined into the file name.");
+            println!("  --one-file-per-operation");
+            println!("      writes every operation into its own file, named by operationId or method & path.");
+            println!("  --dedupe-files");
+            println!("      files identical to an earlier file only reference it, instead of repeating the requests.");
+            println!("  --validate-placeholders");
+            println!("      reports variables used in the requests but missing in the env file, and vice versa.");
+            println!("  --strict");
+            println!("      fails when --validate-placeholders or --validate-unique-paths finds any mismatch.");
+            println!("  --example-from-response");
+            println!("      fills body without an example with example of the 2xx response, without readOnly fields.");
+            println!("  --max-files N");
+            println!("      aborts before writing anything when more than N files would be written.");
+            println!("  --multiline-query");
+            println!("      places every query parameter on its own line.");
+            println!("  --unwrap PROPERTY");
+            println!("      uses content of the wrapper property of responses, e.g. data, with --example-from-response.");
+            println!("  --request-names");
+            println!("      names every request with # @name directive, by its operationId or method & path.");
+            println!("  --body-as-file");
+            println!("      writes request bodies into JSON files next to the requests, referenced by < ./file.json.");
+            println!("  --per-folder-env");
+            println!("      creates env file in every top-level folder, with variables used by its requests.");
+            println!("  --http-version <VERSION>");
+            println!("      appends HTTP version to every request line, one of {}.", HTTP_VERSIONS.join(", "));
+            println!("  --fail-on-warnings");
+            println!("      fails when any warning was reported, e.g. a skipped path or a missing security schema.");
+            println!("  --snippet <LANGUAGE>");
+            println!(
+                "      generates client code next to every request file, one of {}.",
+                SnippetLanguage::ALL.map(|language| language.get_name()).join(", ")
+            );
+            println!("  --verbose-banner");
+            println!("      starts request files with title, version, contact & license of the API.");
+            println!("  --collapse-single-method");
+            println!("      places paths with a single method into flat files, unless a folder of the same name exists.");
+            println!("  --auth-scheme <SCHEME>");
+            println!("      scheme word of the Authorization header of bearer schemes, e.g. Token, defaults to Bearer.");
+            println!("  --emit-preflight");
+            println!("      adds CORS preflight OPTIONS request before every request which needs it, e.g. with JSON body.");
+            println!("  --path-case <CASE>");
+            println!(
+                "      case of the file & folder names, one of {}, defaults to original.",
+                PathCase::ALL.map(|case| case.get_name()).join(", ")
+            );
+            println!("  --max-requests-per-file N");
+            println!("      moves requests over N into continuation files, e.g. customers-2.http, customers-3.http, ...");
+            println!("  --no-auth-comments");
+            println!("      omits security comments, e.g. of API keys, authentication headers are kept.");
+            println!("  --encode-path-params");
+            println!("      percent-encodes path parameters, variables with {{{{encodeURIComponent(name)}}}} of httpyac.");
+            println!("  --expand-variants");
+            println!("      emits request for every member of oneOf body with discriminator, with the discriminator filled.");
+            println!("  --emit-idempotency-key");
+            println!("      adds commented out Idempotency-Key header to POST, PUT, PATCH & DELETE requests.");
+            println!("  --no-empty-folders");
+            println!("      removes folders created by the run which were left without any file.");
+            println!("  --request-naming <SOURCE>");
+            println!(
+                "      source of the request names & operation files, one of {}, defaults to operationId.",
+                RequestNaming::ALL.map(|naming| naming.get_name()).join(", ")
+            );
+            println!("  --group-by-root");
+            println!("      writes one file per first path segment, e.g. users.http, with requests of all its paths.");
+            println!("  --fetch-examples");
+            println!("      fetches external examples (externalValue) of the bodies, instead of referencing them in a comment.");
+            println!("  --method-order <METHODS>");
+            println!("      comma separated order of the requests of the same path, or alphabetical,");
+            println!("      defaults to GET,POST,PUT,PATCH,DELETE.");
+            println!("  --shared-vars-file");
+            println!("      writes the variables into variables.http, which is imported at the top of every request file.");
+            println!("  --emit-curl-comment");
+            println!("      emits equivalent curl command as a comment above every request.");
+            println!("  --readonly-mode <MODE>");
+            println!(
+                "      readOnly properties of the request bodies, one of {}, defaults to omit.",
+                ReadOnlyMode::ALL.map(|mode| mode.get_name()).join(", ")
+            );
+            println!("  --validate-unique-paths");
+            println!("      reports paths which end up in the same file only because of their normalized names, e.g. /Users & /users.");
+            println!("  --include-server-variables-as-env");
+            println!("      adds variables of the server URL into the env file with their defaults, HTTP_HOST uses them.");
+            println!("  --auth-alternatives");
+            println!("      emits a request for every alternative security requirement, instead of only the first one.");
+            println!("  --dump-model PATH");
+            println!("      writes the parsed schema with resolved references as JSON, without generating the files.");
+            println!("  --fetch-timeout SECONDS");
+            println!("      timeout of fetching external examples with --fetch-examples (not of --schema), defaults to 30.");
+            println!("      the run fails with the network exit code 69 when it's hit.");
+            println!("  --strip-readonly-required");
+            println!("      removes readOnly properties from required lists of the request bodies & component schemas.");
+            println!("  --log-format <FORMAT>");
+            println!("      format of warnings & progress, text (default) or json lines with level, message & context.");
+            return Err(AppError::Help);
         });
 
-        let file_path = unwrap_ok_or!(get_argument(&args, &String::from("schema"), &true), _, {
-            eprintln!("Schema path argument is missing!");
-            return Err(exitcode::CONFIG);
-        });
-        let output_path = unwrap_ok_or!(get_argument(&args, &String::from("output"), &true), _, {
-            eprintln!("Output path argument is missing!");
-            return Err(exitcode::CONFIG);
-        });
+        let config = Config::from_args(&args)?;
 
-        let config = Config {
-            file_path,
-            output_path,
-        };
+        if let Some(schema_dir) = &config.schema_dir {
+            if !Path::new(schema_dir).is_dir() {
+                return Err(AppError::Config(format!(
+                    "Schema folder was not found at {}",
+                    schema_dir
+                )));
+            }
+            if config.bundle.is_some() || config.operation.is_some() || config.watch {
+                return Err(AppError::Usage(String::from(
+                    "--schema-dir can't be combined with --bundle, --operation or --watch!",
+                )));
+            }
+        } else if !Path::new(&config.file_path).exists() {
+            return Err(AppError::Config(format!(
+                "Schema file was not found at {}",
+                config.file_path
+            )));
+        }
+
+        if config.stream && config.one_file_per_operation {
+            warn(&String::from("--stream is not supported with --one-file-per-operation, files are buffered"));
+        }
+        if config.stream && config.diff {
+            warn(&String::from("--stream is not supported with --diff, files are buffered"));
+        }
+        if config.stream && config.bundle.is_some() {
+            warn(&String::from("--stream is not supported with --bundle, files are buffered"));
+        }
+        if config.stream && config.single_file {
+            warn(&String::from("--stream is not supported with --single-file, files are buffered"));
+        }
+        if config.stream && config.dedupe_files {
+            warn(&String::from("--dedupe-files is not supported with --stream, all files are written"));
+        }
 
-        if !Path::new(&config.file_path).exists() {
-            eprintln!("Schema file was not found at {}", config.file_path);
-            return Err(exitcode::CONFIG);
+        if config.operation.is_some()
+            || config.bundle.is_some()
+            || config.summary_only
+            || config.dump_model.is_some()
+        {
+            return Ok(Application { config });
         }
 
         let output_dir = Path::new(&config.output_path);
         if !output_dir.exists() {
-            eprintln!("Output folder was not found at {}", config.output_path);
-            return Err(exitcode::CONFIG);
+            return Err(AppError::Config(format!(
+                "Output folder was not found at {}",
+                config.output_path
+            )));
         }
 
-        if !Path::read_dir(&output_dir).unwrap().next().is_none() {
+        if !config.diff
+            && !config.clean
+            && !Path::read_dir(&output_dir).unwrap().next().is_none()
+        {
             let mut buffer = [0; 1];
             let mut reader = std::io::stdin();
 
@@ -66,8 +777,7 @@ impl Application {
             reader.read_exact(&mut buffer).unwrap();
             let answer = (buffer[0] as char).to_lowercase().next().unwrap();
             if answer != 'y' {
-                println!("Aborting.");
-                return Err(exitcode::USAGE);
+                return Err(AppError::Usage(String::from("Aborting.")));
             }
         }
 
@@ -76,61 +786,1166 @@ impl Application {
         return Ok(app);
     }
 
-    pub fn run(&self) -> Result<(), exitcode::ExitCode> {
-        let schema = OpenApi::new(&self.config.file_path);
-        let mut endpoints_map = HashMap::<String, (Vec<String>, Names)>::new();
-        let mut folder_map = HashSet::new();
+    pub fn run(&self) -> Result<(), AppError> {
+        if let Some(schema_dir) = &self.config.schema_dir {
+            self.run_schema_dir(schema_dir)?;
+            return self.check_warnings();
+        }
+        if self.config.watch {
+            let schema_path = Path::new(&self.config.file_path);
+            let files = Vec::from([schema_path
+                .canonicalize()
+                .unwrap_or(schema_path.to_path_buf())]);
+
+            return watch(files, || {
+                let schema = self.load_schema(&self.config.file_path)?;
+                self.generate_output(&schema)?;
+                return Ok(schema.documents);
+            });
+        }
+
+        let schema = self.load_schema(&self.config.file_path)?;
+        self.generate_output(&schema)?;
+        return self.check_warnings();
+    }
+
+    /// Checks whenever the requests are written as they are generated with `--stream`.
+    /// Files grouped by the root segment are known only after all the paths, so they are buffered,
+    /// as well as files which are only compared with the output folder by `--diff`, files written into `--bundle`
+    /// and files combined into a single file, which can be split by `--split-size`.
+    fn is_streamed(&self) -> bool {
+        return self.config.stream
+            && !self.config.one_file_per_operation
+            && !self.config.group_by_root
+            && !self.config.diff
+            && self.config.bundle.is_none()
+            && !self.config.single_file;
+    }
+
+    /// Writes the parsed schema as JSON with `--dump-model`, references are already resolved at this point.
+    fn dump_model(&self, schema: &OpenApi, path: &String) -> Result<(), AppError> {
+        let model = serde_json::to_string_pretty(schema)
+            .map_err(|err| AppError::Parse(format!("Schema could not be serialized: {}", err)))?;
+        return create_file(&model, Path::new(path), &false);
+    }
+
+    /// Loads the schema, with operations of every path sorted by `--method-order`,
+    /// and with readOnly properties removed from `required` with `--strip-readonly-required`.
+    /// Component schemas are parsed once here, they are shared by all the requests.
+    fn load_schema(&self, path: &String) -> Result<OpenApi, AppError> {
+        let mut schema = OpenApi::new(path)?;
+        schema.sort_operations(&self.config.method_order);
+        if self.config.strip_read_only_required {
+            schema.strip_read_only_required();
+        }
+        if let Some(comps) = schema.components.as_mut() {
+            comps.parse_schemas();
+        }
+        return Ok(schema);
+    }
+
+    /// Fails when any warning was reported during the run and `--fail-on-warnings` is set.
+    fn check_warnings(&self) -> Result<(), AppError> {
+        let warnings_count = get_warnings_count();
+        if self.config.fail_on_warnings && warnings_count > 0 {
+            return Err(AppError::Validation(format!(
+                "{} warnings were reported, failing because of --fail-on-warnings",
+                warnings_count
+            )));
+        }
+        return Ok(());
+    }
+
+    /// Generates output of every JSON or YAML schema in the folder into a subfolder named after the schema file,
+    /// e.g. `specs/users.json` -> `output/users/`. Other files & invalid schemas are skipped with a warning.
+    fn run_schema_dir(&self, schema_dir: &String) -> Result<(), AppError> {
+        let entries = fs::read_dir(schema_dir).map_err(|err| {
+            AppError::Io(format!("Schema folder {} could not be read: {}", schema_dir, err))
+        })?;
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let extension = path.extension().and_then(|extension| extension.to_str());
+            if !extension.is_some_and(|extension| SCHEMA_EXTENSIONS.contains(&extension)) {
+                warn_at(
+                    &format!("{} is not a JSON or YAML schema, skipping", path.display()),
+                    &[("path", &path.display().to_string())],
+                );
+                continue;
+            }
+
+            let mut config = self.config.clone();
+            config.schema_dir = None;
+            config.file_path = path.to_string_lossy().to_string();
+            let schema = match self.load_schema(&config.file_path) {
+                Ok(schema) => schema,
+                Err(err) => {
+                    warn(&format!("{}, skipping", err));
+                    continue;
+                }
+            };
+
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            if !config.output_path.is_empty() {
+                config.output_path = join_output_path(&config.output_path, &name.to_string())
+                    .to_string_lossy()
+                    .to_string();
+                if !config.diff {
+                    fs::create_dir_all(&config.output_path).map_err(|err| {
+                        AppError::Io(format!("Unable to create {}: {}", config.output_path, err))
+                    })?;
+                }
+            }
+
+            Application { config }.generate_output(&schema)?;
+        }
+
+        return Ok(());
+    }
+
+    /// Generates the output from the schema, according to the config.
+    fn generate_output(&self, schema: &OpenApi) -> Result<(), AppError> {
+        if self.config.summary_only {
+            self.print_summary(schema);
+            return Ok(());
+        }
+        if let Some(path) = &self.config.dump_model {
+            return self.dump_model(schema, path);
+        }
+        if schema.paths.is_empty() {
+            warn(&format!("No endpoints found in {}, nothing is generated", self.config.file_path));
+            return Ok(());
+        }
+        if let Some(operation_id) = &self.config.operation {
+            return self.run_operation(schema, operation_id);
+        }
+        self.check_unique_paths(schema)?;
+        if self.is_streamed() {
+            return self.run_streamed(schema);
+        }
+
+        let (folders, mut files) = if self.config.single_file {
+            (Vec::new(), self.generate_single_file(schema)?)
+        } else if self.config.group_by_root {
+            (Vec::new(), self.generate_by_root(schema)?)
+        } else if self.config.one_file_per_operation {
+            self.generate_per_operation(schema)?
+        } else {
+            self.generate(schema)?
+        };
+
+        let banner = self.get_banner(schema);
+        for file in files.iter_mut().filter(|file| file.path.ends_with(".http")) {
+            if let Some(header) = self.get_file_header(&banner, &file.path) {
+                file.content = format!("{}\n\n{}", header, file.content);
+            }
+        }
+
+        if self.config.dedupe_files {
+            dedupe_files(&mut files);
+        }
+
+        let mut variables = self.get_initial_variables();
+        for file in &files {
+            // snippets read the variables from the environment, their braces are not placeholders
+            if let Some(language) = &self.config.snippet {
+                if file.path.ends_with(language.get_extension()) {
+                    continue;
+                }
+            }
+            let folder_variables = variables.entry(self.get_env_folder(&file.path)).or_default();
+            collect_variables(&file.content, folder_variables);
+        }
+        files.append(&mut self.create_env_files(&variables, &self.get_server_variables(schema))?);
+        self.check_files_count(files.len())?;
+
+        if let Some(bundle) = &self.config.bundle {
+            return write_bundle(&files, bundle, &self.config.crlf).map_err(|err| {
+                AppError::Io(format!("Bundle could not be written to {}: {}", bundle, err))
+            });
+        }
+
+        if self.config.diff {
+            if report_diff(&files, &self.config.output_path, &self.config.crlf) {
+                return Err(AppError::Validation(String::from(
+                    "Output folder differs from the generated files",
+                )));
+            }
+            return Ok(());
+        }
+
+        self.prepare_output()?;
+        create_folders(&folders, &self.config.output_path)?;
+        let mut written_files = Vec::new();
+        for file in files {
+            let path = join_output_path(&self.config.output_path, &file.path);
+            create_file(&file.content, &path, &self.config.crlf)?;
+            if file.path.ends_with(".http") {
+                written_files.push(path);
+            }
+        }
+        self.remove_empty_folders(&folders)?;
+
+        return self.post_process(&written_files);
+    }
+
+    /// Removes folders created by the run which were left empty, with `--no-empty-folders`.
+    fn remove_empty_folders(&self, folders: &Vec<String>) -> Result<(), AppError> {
+        if !self.config.no_empty_folders {
+            return Ok(());
+        }
+        return remove_empty_folders(folders, &self.config.output_path)
+            .map_err(|err| AppError::Io(format!("Empty folder could not be removed: {}", err)));
+    }
+
+    /// Cleans the output folder when requested and marks it as generated by this tool.
+    fn prepare_output(&self) -> Result<(), AppError> {
+        if self.config.clean {
+            clean_output(&self.config.output_path, &self.config.force).map_err(|err| {
+                AppError::Io(format!("Output folder could not be cleaned: {}", err))
+            })?;
+        }
 
-        for (path_name, endpoint_stucture) in schema.paths {
-            let mut formatted_data = Vec::new();
-            let names = Names::new(&path_name);
+        return create_file(
+            &String::new(),
+            &Path::new(&self.config.output_path).join(MARKER_FILE_NAME),
+            &false,
+        );
+    }
+
+    /// Returns folder (relative to the output folder) of the env file for the file,
+    /// with `--per-folder-env` it's the top-level folder of the file, otherwise the output folder itself.
+    fn get_env_folder(&self, path: &String) -> String {
+        if !self.config.per_folder_env {
+            return String::new();
+        }
+        return get_top_folder(path);
+    }
+
+    /// Returns variables of the env files by their folder,
+    /// the env file in the output folder is created always, unless it's split with `--per-folder-env`.
+    fn get_initial_variables(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut variables = BTreeMap::new();
+        if !self.config.per_folder_env {
+            variables.insert(String::new(), BTreeSet::new());
+        }
+        return variables;
+    }
+
+    /// Creates env file for variables of every folder and checks them with `--validate-placeholders`.
+    /// With `--shared-vars-file` the shared variables file with variables of all the folders is created as well.
+    ///
+    /// `server_variables` are added into every env file, with their value as the default, see `get_server_variables`.
+    fn create_env_files(
+        &self,
+        variables: &BTreeMap<String, BTreeSet<String>>,
+        server_variables: &IndexMap<String, String>,
+    ) -> Result<Vec<GeneratedFile>, AppError> {
+        let mut env_files = Vec::new();
+        for (folder, folder_variables) in variables {
+            let mut folder_variables = folder_variables.clone();
+            folder_variables.extend(server_variables.keys().cloned());
+            let env_file = create_env_file(
+                &folder_variables,
+                server_variables,
+                &self.config.output_path,
+                folder,
+            );
+            self.check_placeholders(&folder_variables, &env_file)?;
+            env_files.push(env_file);
+        }
+
+        if self.config.shared_vars_file {
+            let all_variables = variables.values().flatten().cloned().collect();
+            env_files.push(create_shared_variables_file(&all_variables, &self.config.output_path));
+        }
+        return Ok(env_files);
+    }
+
+    /// Reports paths written into the same file only because of their normalized names with `--validate-unique-paths`,
+    /// with `--strict` the collisions are errors. Single file & files grouped by the root segment merge paths by design.
+    fn check_unique_paths(&self, schema: &OpenApi) -> Result<(), AppError> {
+        if !self.config.validate_unique_paths || self.config.single_file || self.config.group_by_root {
+            return Ok(());
+        }
 
-            for (method, endpoint_info) in endpoint_stucture {
-                let http_data = HttpData::new(&names, &endpoint_info, &method, &schema.components);
-                formatted_data.push(http_data.get_formatted());
+        let collisions = find_file_path_collisions(schema.paths.keys(), &self.config);
+        for (file_path, collision) in &collisions {
+            if self.config.strict {
+                error_at(collision, &[("file_path", file_path)]);
+            } else {
+                warn_at(collision, &[("file_path", file_path)]);
             }
+        }
+
+        if self.config.strict && collisions.len() > 0 {
+            return Err(AppError::Validation(format!(
+                "{} file paths collide after the normalization of the names",
+                collisions.len()
+            )));
+        }
+        return Ok(());
+    }
 
-            if endpoints_map.contains_key(&names.file_path) {
-                endpoints_map
-                    .get_mut(&names.file_path)
-                    .unwrap()
-                    .0
-                    .append(&mut formatted_data);
+    /// Reports mismatches between the used variables & the env file with `--validate-placeholders`,
+    /// with `--strict` the mismatches are errors.
+    fn check_placeholders(
+        &self,
+        variables: &BTreeSet<String>,
+        env_file: &GeneratedFile,
+    ) -> Result<(), AppError> {
+        if !self.config.validate_placeholders {
+            return Ok(());
+        }
+
+        let mismatches = validate_placeholders(variables, env_file);
+        for mismatch in &mismatches {
+            if self.config.strict {
+                error_at(mismatch, &[("env_file", &env_file.path)]);
             } else {
-                endpoints_map.insert(names.file_path.clone(), (formatted_data, names.clone()));
+                warn_at(mismatch, &[("env_file", &env_file.path)]);
+            }
+        }
+
+        if self.config.strict && mismatches.len() > 0 {
+            return Err(AppError::Validation(format!(
+                "{} placeholders don't match the env file",
+                mismatches.len()
+            )));
+        }
+        return Ok(());
+    }
+
+    /// Fails when the number of files exceeds `--max-files`.
+    fn check_files_count(&self, files_count: usize) -> Result<(), AppError> {
+        match self.config.max_files {
+            Some(max_files) if files_count > max_files => Err(AppError::Validation(format!(
+                "{} files would be written, which is more than --max-files {}",
+                files_count, max_files
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs the post-process command for all the written files,
+    /// failures are reported and the run ends with an error after all files were processed.
+    fn post_process(&self, paths: &Vec<PathBuf>) -> Result<(), AppError> {
+        let command = match &self.config.post_process {
+            Some(command) => command,
+            None => return Ok(()),
+        };
+
+        let mut failed = 0;
+        for path in paths {
+            if let Err(err) = run_command(command, path) {
+                error_at(
+                    &format!("post-process of {} failed: {}", path.display(), err),
+                    &[("path", &path.display().to_string())],
+                );
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            return Err(AppError::Command(format!(
+                "post-process failed for {} of {} files",
+                failed,
+                paths.len()
+            )));
+        }
+        return Ok(());
+    }
+
+    /// Prints request of the operation with provided operationId to stdout.
+    fn run_operation(&self, schema: &OpenApi, operation_id: &String) -> Result<(), AppError> {
+        for (path_name, path_item) in &schema.paths {
+            for (method, endpoint_info) in &path_item.operations {
+                if endpoint_info.operation_id.as_ref() != Some(operation_id) {
+                    continue;
+                }
+
+                let http_data = HttpData::new(
+                    &Names::new(path_name, &self.config),
+                    endpoint_info,
+                    method,
+                    &schema.components,
+                    &self.config,
+                )?;
+                println!("{}", http_data.get_formatted());
+                return Ok(());
+            }
+        }
+
+        return Err(AppError::Validation(format!(
+            "Operation {} was not found in the schema",
+            operation_id
+        )));
+    }
+
+    /// With `--body-as-file`, moves body of the request into a JSON file named after the operation,
+    /// bodies of `--expand-variants` get the discriminator value appended, e.g. `create-pet-dog.json`.
+    /// Returns names of the body files & their content, names are numbered when already used.
+    fn move_body_to_file(
+        &self,
+        http_data: &mut HttpData,
+        operation_file_name: &String,
+        used_file_names: &mut HashSet<String>,
+    ) -> Vec<(String, String)> {
+        if !self.config.body_as_file {
+            return Vec::new();
+        }
+
+        let mut get_file_name = |variant: Option<&String>| {
+            let stem = match variant {
+                Some(variant) => format!("{}-{}", operation_file_name, variant),
+                None => operation_file_name.clone(),
+            };
+            let mut file_name = format!("{}.json", stem);
+            let mut suffix = 1;
+            while used_file_names.contains(&file_name) {
+                suffix += 1;
+                file_name = format!("{}-{}.json", stem, suffix);
+            }
+            used_file_names.insert(file_name.clone());
+            return file_name;
+        };
+        return http_data.move_body_to_file(&mut get_file_name);
+    }
+
+    /// Adds operations of a path to the number of requests deciding placement of its file,
+    /// see `get_final_file_path`. With `--collapse-single-method` only operations of a single path
+    /// are counted, so a file is placed into a folder only for a path with more methods.
+    fn add_requests_count(&self, requests_count: usize, path_operations_count: usize) -> usize {
+        if self.config.collapse_single_method {
+            return requests_count.max(path_operations_count);
+        }
+        return requests_count + path_operations_count;
+    }
+
+    /// Reports file of paths with a single method, which is placed into a folder with `--collapse-single-method`
+    /// anyway, since a folder of the same name exists.
+    fn report_folder_collision(&self, names: &Names, requests_count: usize, folder_map: &HashSet<String>) {
+        if self.config.collapse_single_method
+            && requests_count <= 1
+            && collides_with_folder(names, folder_map)
+        {
+            warn(&format!(
+                "{}.http collides with folder of the same name, it's placed into the folder",
+                names.file_path
+            ));
+        }
+    }
+
+    /// Creates banner of the request files with `--verbose-banner`, see `create_banner`.
+    /// With `--include-server-variables-as-env`, allowed values of the server variables are listed as well.
+    fn get_banner(&self, schema: &OpenApi) -> Option<String> {
+        let mut lines = Vec::new();
+        if self.config.verbose_banner {
+            lines.extend(schema.info.as_ref().and_then(create_banner));
+        }
+
+        if self.config.include_server_variables_as_env {
+            let server_variables = schema
+                .servers
+                .iter()
+                .flatten()
+                .next()
+                .and_then(|server| server.variables.as_ref());
+            for (name, variable) in server_variables.into_iter().flatten() {
+                if let Some(values) = &variable.r#enum {
+                    lines.push(format!("# {{{{{}}}}}: {}", name, values.join(", ")));
+                }
+            }
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+        return Some(lines.join("\n"));
+    }
+
+    /// Returns variables of the first server of the schema with their defaults with `--include-server-variables-as-env`,
+    /// HTTP_HOST is the server URL using the variables, e.g. `https://{{region}}.example.com`.
+    fn get_server_variables(&self, schema: &OpenApi) -> IndexMap<String, String> {
+        let mut variables = IndexMap::new();
+        if !self.config.include_server_variables_as_env {
+            return variables;
+        }
+
+        if let Some(server) = schema.servers.iter().flatten().next() {
+            variables.insert(String::from("HTTP_HOST"), server.get_templated_url());
+            for (name, variable) in server.variables.iter().flatten() {
+                variables.insert(name.clone(), variable.default.clone());
+            }
+        }
+        return variables;
+    }
+
+    /// Creates beginning of the request file (relative to the output folder), i.e. import of the shared
+    /// variables file with `--shared-vars-file` & the banner. None is returned when there is neither of them.
+    fn get_file_header(&self, banner: &Option<String>, path: &String) -> Option<String> {
+        let mut lines = Vec::new();
+        if self.config.shared_vars_file {
+            lines.push(get_shared_variables_import(path));
+        }
+        if let Some(banner) = banner {
+            lines.push(banner.clone());
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+        return Some(lines.join("\n"));
+    }
+
+    /// Creates snippet of the request with `--snippet`.
+    fn get_snippet(&self, http_data: &HttpData) -> Option<String> {
+        return self
+            .config
+            .snippet
+            .map(|language| http_data.get_snippet(&language));
+    }
+
+    /// Creates file with the snippets next to the request file with `--snippet`,
+    /// e.g. `/customers/customers.py` for `/customers/customers.http`.
+    fn create_snippet_file(&self, path: &String, snippets: &Vec<String>) -> Option<GeneratedFile> {
+        let language = self.config.snippet?;
+        if snippets.is_empty() {
+            return None;
+        }
+
+        let prelude = language.get_prelude();
+        let content = std::iter::once(&prelude)
+            .filter(|prelude| !prelude.is_empty())
+            .chain(snippets.iter())
+            .map(|part| part.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n\n");
+
+        return Some(GeneratedFile {
+            path: get_snippet_path(path, &language),
+            content,
+        });
+    }
+
+    /// Prints table of all the operations sorted by path, e.g. `GET  /customers — List customers`.
+    /// Summary of the path is used for operations without their own summary.
+    fn print_summary(&self, schema: &OpenApi) {
+        let mut rows = Vec::new();
+        for (path_name, path_item) in &schema.paths {
+            for (method, endpoint_info) in &path_item.operations {
+                let summary = endpoint_info.summary.as_ref().or(path_item.summary.as_ref());
+                rows.push((method.get_value(), path_name, summary));
+            }
+        }
+        // sort is stable, so methods of the same path keep the order of `--method-order`
+        rows.sort_by_key(|(_, a, _)| *a);
+
+        let method_width = rows.iter().map(|(method, _, _)| method.len()).max().unwrap_or(0);
+        let path_width = rows.iter().map(|(_, path, _)| path.len()).max().unwrap_or(0);
+        for (method, path, summary) in rows {
+            match summary {
+                Some(summary) => println!(
+                    "{:method_width$} {:path_width$} — {}",
+                    method, path, summary
+                ),
+                None => println!("{:method_width$} {}", method, path),
+            }
+        }
+    }
+
+    /// Generates all the requests into a single file, separated by `###`.
+    /// With `--split-size`, requests are split into numbered parts instead.
+    fn generate_single_file(&self, schema: &OpenApi) -> Result<Vec<GeneratedFile>, AppError> {
+        let mut requests = Vec::new();
+        let mut snippets = Vec::new();
+        let mut body_files = Vec::new();
+        let mut used_names = HashSet::new();
+        let mut used_file_names = HashSet::new();
+        for (path_name, path_item) in &schema.paths {
+            let names = Names::new(path_name, &self.config);
+            for (method, endpoint_info) in &path_item.operations {
+                let mut http_data = HttpData::new(
+                    &names,
+                    endpoint_info,
+                    method,
+                    &schema.components,
+                    &self.config,
+                )?;
+                http_data.make_name_unique(&mut used_names);
+                snippets.extend(self.get_snippet(&http_data));
+                let file_name = names.get_operation_file_name(method, endpoint_info, &self.config);
+                for (body_file_name, body) in
+                    self.move_body_to_file(&mut http_data, &file_name, &mut used_file_names)
+                {
+                    body_files.push(GeneratedFile {
+                        path: format!("/{}", body_file_name),
+                        content: body,
+                    });
+                }
+                requests.push(http_data.get_formatted());
+            }
+        }
+
+        let mut files = match self.config.split_size {
+            Some(split_size) => requests
+                .chunks(split_size)
+                .enumerate()
+                .map(|(i, chunk)| GeneratedFile {
+                    path: format!("/part-{:03}.http", i + 1),
+                    content: chunk.join(REQUEST_SEPARATOR),
+                })
+                .collect(),
+            None => Vec::from([GeneratedFile {
+                path: "/requests.http".to_owned(),
+                content: requests.join(REQUEST_SEPARATOR),
+            }]),
+        };
+        files.append(&mut body_files);
+        // snippets are not split, they are not meant to be opened in the HTTP client
+        files.extend(self.create_snippet_file(&String::from("/requests.http"), &snippets));
+
+        return Ok(files);
+    }
+
+    /// Generates a file for every first segment of the paths, e.g. `/users.http` with requests of `/users`
+    /// & `/users/{id}/orders`, separated by `###`. Files are placed directly in the output folder.
+    fn generate_by_root(&self, schema: &OpenApi) -> Result<Vec<GeneratedFile>, AppError> {
+        let mut roots = IndexMap::<String, FileRequests>::new();
+        let mut used_names = HashMap::<String, HashSet<String>>::new();
+        let mut used_file_names = HashSet::new();
+
+        for (path_name, path_item) in &schema.paths {
+            if path_item.operations.is_empty() {
+                warn_at(
+                    &format!("{} has no operations, skipping", path_name),
+                    &[("path", path_name)],
+                );
+                continue;
+            }
+
+            let names = Names::new(path_name, &self.config);
+            let root = names.folders.first().unwrap_or(&names.file_name).clone();
+            let file_requests = roots.entry(root.clone()).or_insert_with(|| FileRequests {
+                names: names.clone(),
+                formatted: Vec::new(),
+                request_lines: Vec::new(),
+                snippets: Vec::new(),
+                body_files: Vec::new(),
+                requests_count: 0,
+            });
+            file_requests.requests_count += path_item.operations.len();
+
+            let file_used_names = used_names.entry(root).or_default();
+            for (method, endpoint_info) in &path_item.operations {
+                let mut http_data = HttpData::new(
+                    &names,
+                    endpoint_info,
+                    method,
+                    &schema.components,
+                    &self.config,
+                )?;
+                http_data.make_name_unique(file_used_names);
+                file_requests.snippets.extend(self.get_snippet(&http_data));
+                let file_name = names.get_operation_file_name(method, endpoint_info, &self.config);
+                file_requests.body_files.extend(self.move_body_to_file(
+                    &mut http_data,
+                    &file_name,
+                    &mut used_file_names,
+                ));
+                file_requests.formatted.push(http_data.get_formatted());
+                file_requests.request_lines.push(http_data.get_request_line());
+            }
+        }
+
+        let mut files = Vec::new();
+        let mut index_entries = Vec::new();
+        for (root, file_requests) in roots {
+            let path = format!("/{}.http", root);
+            for (file_name, body) in file_requests.body_files {
+                files.push(GeneratedFile {
+                    path: format!("/{}", file_name),
+                    content: body,
+                });
+            }
+            files.extend(self.create_snippet_file(&path, &file_requests.snippets));
+
+            let max_requests = self.config.max_requests_per_file.unwrap_or(usize::MAX);
+            let parts = file_requests
+                .formatted
+                .chunks(max_requests)
+                .zip(file_requests.request_lines.chunks(max_requests));
+            for (part, (formatted, request_lines)) in parts.enumerate() {
+                let part_path = get_continuation_path(&path, part);
+                if self.config.emit_index {
+                    index_entries.push((part_path.clone(), request_lines.to_vec()));
+                }
+                files.push(GeneratedFile {
+                    path: part_path,
+                    content: formatted.join(REQUEST_SEPARATOR),
+                });
+            }
+        }
+
+        if self.config.emit_index {
+            files.push(create_index(&index_entries));
+        }
+
+        return Ok(files);
+    }
+
+    /// Generates all the files in memory, together with folders which need to be created for them.
+    fn generate(&self, schema: &OpenApi) -> Result<(Vec<String>, Vec<GeneratedFile>), AppError> {
+        let mut endpoints_map = IndexMap::<String, FileRequests>::new();
+        // names of the requests have to be unique within the file
+        let mut used_names = HashMap::<String, HashSet<String>>::new();
+        // folders of the request files are known only at the end, so body files are unique globally
+        let mut used_file_names = HashSet::new();
+        let mut folder_map = HashSet::new();
+        let mut folders = Vec::new();
+
+        for (path_name, path_item) in &schema.paths {
+            if path_item.operations.is_empty() {
+                warn_at(
+                    &format!("{} has no operations, skipping", path_name),
+                    &[("path", path_name)],
+                );
+                continue;
+            }
+
+            let names = Names::new(path_name, &self.config);
+            let file_requests = endpoints_map
+                .entry(names.file_path.clone())
+                .or_insert_with(|| FileRequests {
+                    names: names.clone(),
+                    formatted: Vec::new(),
+                    request_lines: Vec::new(),
+                    snippets: Vec::new(),
+                    body_files: Vec::new(),
+                    requests_count: 0,
+                });
+            file_requests.requests_count =
+                self.add_requests_count(file_requests.requests_count, path_item.operations.len());
+
+            let file_used_names = used_names.entry(names.file_path.clone()).or_default();
+            for (method, endpoint_info) in &path_item.operations {
+                let mut http_data = HttpData::new(
+                    &names,
+                    endpoint_info,
+                    method,
+                    &schema.components,
+                    &self.config,
+                )?;
+                http_data.make_name_unique(file_used_names);
+                file_requests.snippets.extend(self.get_snippet(&http_data));
+                let file_name = names.get_operation_file_name(method, endpoint_info, &self.config);
+                file_requests.body_files.extend(self.move_body_to_file(
+                    &mut http_data,
+                    &file_name,
+                    &mut used_file_names,
+                ));
+                file_requests.formatted.push(http_data.get_formatted());
+                file_requests.request_lines.push(http_data.get_request_line());
+            }
+
+            folder_map.extend(names.folders.clone());
+            folders.extend(names.folders);
+        }
+
+        let mut files = Vec::new();
+        let mut index_entries = Vec::new();
+        for (_, file_requests) in endpoints_map {
+            let names = &file_requests.names;
+            if file_requests.requests_count > 1 {
+                // file holding more requests is placed into its own folder, see `get_final_file_path`
+                folders.push(names.file_path.clone());
+            }
+
+            self.report_folder_collision(names, file_requests.requests_count, &folder_map);
+            let path = get_final_file_path(names, file_requests.requests_count, &folder_map);
+            // body files are placed next to the request file, which references them
+            let folder = path.rsplit_once('/').map_or("", |(folder, _)| folder);
+            for (file_name, body) in file_requests.body_files {
+                files.push(GeneratedFile {
+                    path: format!("{}/{}", folder, file_name),
+                    content: body,
+                });
+            }
+            files.extend(self.create_snippet_file(&path, &file_requests.snippets));
+
+            let max_requests = self.config.max_requests_per_file.unwrap_or(usize::MAX);
+            let parts = file_requests
+                .formatted
+                .chunks(max_requests)
+                .zip(file_requests.request_lines.chunks(max_requests));
+            for (part, (formatted, request_lines)) in parts.enumerate() {
+                let part_path = get_continuation_path(&path, part);
+                if self.config.emit_index {
+                    index_entries.push((part_path.clone(), request_lines.to_vec()));
+                }
+                files.push(GeneratedFile {
+                    path: part_path,
+                    content: formatted.join("\n\n"),
+                });
+            }
+        }
+
+        if self.config.emit_index {
+            files.push(create_index(&index_entries));
+        }
+
+        return Ok((folders, files));
+    }
+
+    /// Generates a file for every operation, placed in the folder of its path,
+    /// e.g. `/customers/getCustomer.http` for operation `getCustomer` of `/customers/{id}`.
+    fn generate_per_operation(
+        &self,
+        schema: &OpenApi,
+    ) -> Result<(Vec<String>, Vec<GeneratedFile>), AppError> {
+        let mut folders = Vec::new();
+        let mut files = Vec::new();
+        let mut index_entries = Vec::new();
+        let mut used_paths = HashSet::new();
+
+        for (path_name, path_item) in &schema.paths {
+            if path_item.operations.is_empty() {
+                warn_at(
+                    &format!("{} has no operations, skipping", path_name),
+                    &[("path", path_name)],
+                );
+                continue;
             }
 
+            let names = Names::new(path_name, &self.config);
+            folders.extend(names.folders.clone());
+            folders.push(names.file_path.clone());
+
+            for (method, endpoint_info) in &path_item.operations {
+                let mut http_data = HttpData::new(
+                    &names,
+                    endpoint_info,
+                    method,
+                    &schema.components,
+                    &self.config,
+                )?;
+
+                let file_name = names.get_operation_file_name(method, endpoint_info, &self.config);
+                let mut path = format!("{}/{}.http", &names.file_path, file_name);
+                // names of different operations could be same after the conversion, number them
+                let mut suffix = 1;
+                while used_paths.contains(&path) {
+                    suffix += 1;
+                    path = format!("{}/{}-{}.http", &names.file_path, file_name, suffix);
+                }
+                used_paths.insert(path.clone());
+
+                let snippets = Vec::from_iter(self.get_snippet(&http_data));
+                files.extend(self.create_snippet_file(&path, &snippets));
+                // body file shares the name of the request file, which is already unique
+                let request_file_name = path.rsplit('/').next().unwrap_or_default();
+                let stem = request_file_name.trim_end_matches(".http").to_owned();
+                for (body_file_name, body) in
+                    self.move_body_to_file(&mut http_data, &stem, &mut HashSet::new())
+                {
+                    files.push(GeneratedFile {
+                        path: format!("{}/{}", &names.file_path, body_file_name),
+                        content: body,
+                    });
+                }
+
+                if self.config.emit_index {
+                    index_entries.push((path.clone(), Vec::from([http_data.get_request_line()])));
+                }
+                files.push(GeneratedFile {
+                    path,
+                    content: http_data.get_formatted(),
+                });
+            }
+        }
+
+        if self.config.emit_index {
+            files.push(create_index(&index_entries));
+        }
+
+        return Ok((folders, files));
+    }
+
+    /// Writes the snippet into the snippet file next to the request file, see `create_snippet_file`.
+    /// The file is created with the first snippet, later snippets are appended.
+    fn write_snippet(
+        &self,
+        snippet: &String,
+        request_file_path: &PathBuf,
+        written_snippet_files: &mut HashSet<PathBuf>,
+    ) -> Result<(), AppError> {
+        let language = match &self.config.snippet {
+            Some(language) => language,
+            None => return Ok(()),
+        };
+        let path = request_file_path.with_extension(language.get_extension().trim_start_matches('.'));
+
+        if written_snippet_files.contains(&path) {
+            return append_file(&format!("\n\n{}", snippet), &path, &self.config.crlf);
+        }
+        written_snippet_files.insert(path.clone());
+        let prelude = language.get_prelude();
+        if prelude.is_empty() {
+            return create_file(snippet, &path, &self.config.crlf);
+        }
+        return create_file(&format!("{}\n\n{}", prelude, snippet), &path, &self.config.crlf);
+    }
+
+    /// Writes requests of every path as soon as they are generated.
+    /// Layout of the files is computed upfront from the paths, so the output is same as from `run`.
+    fn run_streamed(&self, schema: &OpenApi) -> Result<(), AppError> {
+        let mut operations_count = HashMap::<String, usize>::new();
+        let mut folder_map = HashSet::new();
+        // names & number of requests of every file, the files are counted before anything is written
+        let mut files = IndexMap::<String, (Names, usize)>::new();
+        let mut body_files_count = 0;
+
+        for (path_name, path_item) in &schema.paths {
+            if path_item.operations.is_empty() {
+                warn_at(
+                    &format!("{} has no operations, skipping", path_name),
+                    &[("path", path_name)],
+                );
+                continue;
+            }
+
+            let names = Names::new(path_name, &self.config);
+            let count = operations_count.entry(names.file_path.clone()).or_insert(0);
+            *count = self.add_requests_count(*count, path_item.operations.len());
             folder_map.extend(names.folders.clone());
+            if self.config.body_as_file {
+                body_files_count += path_item
+                    .operations
+                    .values()
+                    .map(|endpoint_info| {
+                        HttpData::get_body_files_count(
+                            endpoint_info,
+                            &schema.components,
+                            &self.config,
+                        )
+                    })
+                    .sum::<usize>();
+            }
+            let (_, requests_count) = files
+                .entry(names.file_path.clone())
+                .or_insert_with(|| (names, 0));
+            *requests_count += path_item.operations.len();
+        }
 
-            create_folders(&names.folders, &self.config.output_path);
+        // request files with their continuations & snippets, body files, env files, shared variables & the index,
+        // same files as in the buffered run
+        let mut env_folders: BTreeSet<String> = self.get_initial_variables().into_keys().collect();
+        let mut files_count = body_files_count
+            + self.config.shared_vars_file as usize
+            + self.config.emit_index as usize;
+        for (file_path, (names, requests_count)) in &files {
+            let relative_file_path =
+                get_final_file_path(names, operations_count[file_path], &folder_map);
+            files_count += self
+                .config
+                .max_requests_per_file
+                .map_or(1, |max| requests_count.div_ceil(max));
+            files_count += self.config.snippet.is_some() as usize;
+            env_folders.insert(self.get_env_folder(&relative_file_path));
         }
+        files_count += env_folders.len();
+        self.check_files_count(files_count)?;
+        self.prepare_output()?;
 
-        // create files for all the endpoints
-        for (_, (data, names)) in endpoints_map {
-            let final_file_path;
-            if data.len() > 1 {
-                // if there are more endpoints with same file_path, create folder for them
+        let mut written_files = Vec::new();
+        let mut variables = self.get_initial_variables();
+        let mut index_entries = IndexMap::<String, Vec<String>>::new();
+        let mut used_names = HashMap::<String, HashSet<String>>::new();
+        let mut used_file_names = HashSet::new();
+        let mut written_snippet_files = HashSet::new();
+        let mut requests_written = HashMap::<String, usize>::new();
+        let mut created_folders = Vec::new();
+        let banner = self.get_banner(schema);
+        for (path_name, path_item) in &schema.paths {
+            if path_item.operations.is_empty() {
+                continue;
+            }
+
+            let names = Names::new(path_name, &self.config);
+            let count = operations_count[&names.file_path];
+
+            let relative_file_path = get_final_file_path(&names, count, &folder_map);
+            create_folders(&names.folders, &self.config.output_path)?;
+            created_folders.extend(names.folders.clone());
+            if relative_file_path.starts_with(&format!("{}/", names.file_path)) {
+                // the file is placed into its own folder, which could be needed only by later paths
                 create_folders(
                     &Vec::from([names.file_path.clone()]),
                     &self.config.output_path,
-                );
-                final_file_path = format!("{}/{}.http", &names.file_path, &names.file_name);
-            } else if folder_map.contains(&names.file_path) {
-                // folder with same name as this file would have exist,
-                // place this file into the existing file
-                final_file_path = format!("{}/{}.http", &names.file_path, &names.file_name);
-            } else {
-                final_file_path = format!("{}.http", &names.file_path);
+                )?;
+                created_folders.push(names.file_path.clone());
+            }
+            let final_file_path = join_output_path(&self.config.output_path, &relative_file_path);
+            if !written_files.contains(&final_file_path) {
+                self.report_folder_collision(&names, count, &folder_map);
+            }
+            let file_used_names = used_names.entry(relative_file_path.clone()).or_default();
+            let folder_variables = variables
+                .entry(self.get_env_folder(&relative_file_path))
+                .or_default();
+
+            for (method, endpoint_info) in &path_item.operations {
+                let mut http_data = HttpData::new(
+                    &names,
+                    endpoint_info,
+                    method,
+                    &schema.components,
+                    &self.config,
+                )?;
+                http_data.make_name_unique(file_used_names);
+
+                if let Some(snippet) = self.get_snippet(&http_data) {
+                    self.write_snippet(&snippet, &final_file_path, &mut written_snippet_files)?;
+                }
+                let file_name = names.get_operation_file_name(method, endpoint_info, &self.config);
+                for (body_file_name, body) in
+                    self.move_body_to_file(&mut http_data, &file_name, &mut used_file_names)
+                {
+                    collect_variables(&body, folder_variables);
+                    let body_file_path = final_file_path.with_file_name(body_file_name);
+                    create_file(&body, &body_file_path, &self.config.crlf)?;
+                }
+
+                // snippets & body files stay next to the first part, only the requests are split
+                let written = requests_written.entry(relative_file_path.clone()).or_insert(0);
+                let part = self.config.max_requests_per_file.map_or(0, |max| *written / max);
+                *written += 1;
+                let part_file_path = get_continuation_path(&relative_file_path, part);
+                let final_part_path = join_output_path(&self.config.output_path, &part_file_path);
+
+                let formatted_data = http_data.get_formatted();
+                collect_variables(&formatted_data, folder_variables);
+                if self.config.emit_index {
+                    index_entries
+                        .entry(part_file_path.clone())
+                        .or_insert_with(Vec::new)
+                        .push(http_data.get_request_line());
+                }
+
+                if written_files.contains(&final_part_path) {
+                    append_file(
+                        &format!("\n\n{}", formatted_data),
+                        &final_part_path,
+                        &self.config.crlf,
+                    )?;
+                } else {
+                    let content = match self.get_file_header(&banner, &part_file_path) {
+                        Some(header) => format!("{}\n\n{}", header, formatted_data),
+                        None => formatted_data,
+                    };
+                    create_file(&content, &final_part_path, &self.config.crlf)?;
+                    written_files.push(final_part_path);
+                }
             }
+        }
 
+        for env_file in self.create_env_files(&variables, &self.get_server_variables(schema))? {
             create_file(
-                &data.join("\n\n"),
-                &format!("{}{}", &self.config.output_path, &final_file_path),
-            );
+                &env_file.content,
+                &join_output_path(&self.config.output_path, &env_file.path),
+                &self.config.crlf,
+            )?;
         }
 
-        return Ok(());
+        if self.config.emit_index {
+            let index = create_index(&index_entries.into_iter().collect());
+            create_file(
+                &index.content,
+                &join_output_path(&self.config.output_path, &index.path),
+                &self.config.crlf,
+            )?;
+        }
+        self.remove_empty_folders(&created_folders)?;
+
+        return self.post_process(&written_files);
+    }
+}
+
+/// Checks whenever a folder of the same name as the file of the endpoint is needed by other endpoints.
+/// Folders are stored without the leading slash, e.g. `customers/orders`.
+fn collides_with_folder(names: &Names, folder_map: &HashSet<String>) -> bool {
+    return folder_map.contains(names.file_path.trim_start_matches('/'));
+}
+
+/// Returns path of the file (relative to the output folder) for requests of the endpoint.
+///
+/// The file is placed into a folder named after the endpoint, e.g. `/customers/customers.http`, when
+/// - the file holds more requests, e.g. GET & POST of `/customers`, so they are grouped in the folder,
+/// - or the folder is needed anyway, e.g. for requests of `/customers/{id}`, so the file is kept
+///   together with them, instead of having `/customers.http` next to `/customers` folder.
+///
+/// Otherwise the file is placed directly into its parent folder, e.g. `/customers.http`.
+/// The decision depends only on the counts & the set of folders, not on the order of the paths.
+fn get_final_file_path(
+    names: &Names,
+    operations_count: usize,
+    folder_map: &HashSet<String>,
+) -> String {
+    let has_more_requests = operations_count > 1;
+
+    if has_more_requests || collides_with_folder(names, folder_map) {
+        return format!("{}/{}.http", &names.file_path, &names.file_name);
     }
+    return format!("{}.http", &names.file_path);
+}
+
+/// Parses `--method-order`, i.e. comma separated methods, e.g. `GET,POST`, or `alphabetical`.
+/// Methods which are not listed keep the default order after the listed ones.
+fn parse_method_order(order: &String) -> Result<Vec<HttpMethod>, AppError> {
+    if order == "alphabetical" {
+        let mut methods = Vec::from(HttpMethod::ALL);
+        methods.sort_by_key(|method| method.get_value());
+        return Ok(methods);
+    }
+
+    let mut methods = Vec::new();
+    for name in order.split(',').map(|name| name.trim()).filter(|name| name.len() > 0) {
+        match HttpMethod::from_name(name) {
+            Some(method) if !methods.contains(&method) => methods.push(method),
+            Some(_) => (),
+            None => {
+                return Err(AppError::Usage(format!(
+                    "Method order has to be alphabetical or a comma separated list of {}!",
+                    HttpMethod::ALL.map(|method| method.get_value()).join(", ")
+                )));
+            }
+        }
+    }
+    for method in HttpMethod::ALL {
+        if !methods.contains(&method) {
+            methods.push(method);
+        }
+    }
+    return Ok(methods);
+}
+
+/// Returns path of the part of the request file with `--max-requests-per-file`,
+/// the first part keeps the path, e.g. `/customers.http`, `/customers-2.http`, `/customers-3.http`, ...
+fn get_continuation_path(path: &String, part: usize) -> String {
+    if part == 0 {
+        return path.clone();
+    }
+    return format!("{}-{}.http", path.trim_end_matches(".http"), part + 1);
+}
+
+/// Returns path of the snippet file for the request file, e.g. `/customers.py` for `/customers.http`.
+fn get_snippet_path(path: &String, language: &SnippetLanguage) -> String {
+    return format!("{}{}", path.trim_end_matches(".http"), language.get_extension());
 }
 
 fn get_argument(args: &Vec<String>, name: &String, with_value: &bool) -> Result<String, ()> {
@@ -150,3 +1965,258 @@ fn get_argument(args: &Vec<String>, name: &String, with_value: &bool) -> Result<
 
     return Ok(args[arg.unwrap() + 1].clone());
 }
+
+/// Checks whenever the header is in `Key: Value` format.
+fn is_valid_header(header: &String) -> bool {
+    return header
+        .split_once(':')
+        .is_some_and(|(name, _)| !name.trim().is_empty());
+}
+
+/// Reads headers from the file with one `Key: Value` header per line,
+/// blank lines & comments (lines starting with `#`) are skipped.
+fn read_headers_file(path: &String) -> Result<Vec<String>, AppError> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        AppError::Config(format!("Base headers file {} could not be read: {}", path, err))
+    })?;
+
+    let mut headers = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let header = line.to_owned();
+        if !is_valid_header(&header) {
+            return Err(AppError::Config(format!(
+                "Line {} of {} has to be in \"Key: Value\" format!",
+                i + 1,
+                path
+            )));
+        }
+        headers.push(header);
+    }
+
+    return Ok(headers);
+}
+
+/// Returns values of all occurrences of the argument, e.g. `--header A --header B`.
+fn get_arguments(args: &Vec<String>, name: &String) -> Vec<String> {
+    let flag = format!("--{}", name);
+    return args
+        .windows(2)
+        .filter(|pair| pair[0] == flag && !pair[1].starts_with("--"))
+        .map(|pair| pair[1].clone())
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_names(path: &str) -> Names {
+        return Names::new(&String::from(path), &Config::from_test_args(&[]));
+    }
+
+    #[test]
+    fn single_request_file_is_flat() {
+        let folder_map = HashSet::from([String::from("orders")]);
+
+        assert_eq!(
+            get_final_file_path(&get_names("/customers"), 1, &folder_map),
+            "/customers.http"
+        );
+    }
+
+    #[test]
+    fn file_with_more_requests_is_nested() {
+        assert_eq!(
+            get_final_file_path(&get_names("/customers"), 2, &HashSet::new()),
+            "/customers/customers.http"
+        );
+    }
+
+    #[test]
+    fn file_colliding_with_folder_is_nested() {
+        // folder of `/customers/orders` has the name of the `/customers` file
+        let folder_map = HashSet::from([String::from("customers")]);
+
+        assert_eq!(
+            get_final_file_path(&get_names("/customers"), 1, &folder_map),
+            "/customers/customers.http"
+        );
+        assert_eq!(
+            get_final_file_path(&get_names("/customers/orders"), 1, &folder_map),
+            "/customers/orders.http"
+        );
+    }
+
+    #[test]
+    fn nested_file_collides_with_nested_folder_only() {
+        let folder_map = HashSet::from([
+            String::from("customers"),
+            String::from("customers/orders"),
+            String::from("orders-archive"),
+        ]);
+
+        assert_eq!(
+            get_final_file_path(&get_names("/customers/orders"), 1, &folder_map),
+            "/customers/orders/orders.http"
+        );
+        assert_eq!(get_final_file_path(&get_names("/orders"), 1, &folder_map), "/orders.http");
+    }
+
+    #[test]
+    fn collapse_single_method_counts_requests_per_path() {
+        let app = Application {
+            config: Config::from_test_args(&["--collapse-single-method"]),
+        };
+        // GET /customers & GET /customers/{id} share the file, each path has a single method
+        let requests_count = app.add_requests_count(app.add_requests_count(0, 1), 1);
+
+        assert_eq!(requests_count, 1);
+        assert_eq!(
+            get_final_file_path(&get_names("/customers"), requests_count, &HashSet::new()),
+            "/customers.http"
+        );
+        assert_eq!(app.add_requests_count(requests_count, 2), 2);
+
+        let app = Application {
+            config: Config::from_test_args(&[]),
+        };
+        assert_eq!(app.add_requests_count(app.add_requests_count(0, 1), 1), 2);
+    }
+
+    #[test]
+    fn collapse_single_method_is_nested_on_folder_collision() {
+        let app = Application {
+            config: Config::from_test_args(&["--collapse-single-method"]),
+        };
+        let folder_map = HashSet::from([String::from("customers")]);
+        let requests_count = app.add_requests_count(0, 1);
+
+        assert_eq!(
+            get_final_file_path(&get_names("/customers"), requests_count, &folder_map),
+            "/customers/customers.http"
+        );
+    }
+
+    #[test]
+    fn method_order_is_honored() {
+        let mut schema: OpenApi = serde_json::from_value(serde_json::json!({
+            "paths": { "/users": { "delete": {}, "get": {}, "patch": {}, "post": {} } }
+        }))
+        .unwrap();
+        let get_methods = |schema: &OpenApi| -> Vec<HttpMethod> {
+            return schema.paths["/users"].operations.keys().cloned().collect();
+        };
+        let get_method_order = |args: &[&str]| Config::from_test_args(args).method_order;
+
+        schema.sort_operations(&get_method_order(&[]));
+        assert_eq!(
+            get_methods(&schema),
+            [HttpMethod::get, HttpMethod::post, HttpMethod::patch, HttpMethod::delete]
+        );
+
+        schema.sort_operations(&get_method_order(&["--method-order", "delete,PATCH"]));
+        assert_eq!(
+            get_methods(&schema),
+            [HttpMethod::delete, HttpMethod::patch, HttpMethod::get, HttpMethod::post]
+        );
+
+        schema.sort_operations(&get_method_order(&["--method-order", "alphabetical"]));
+        assert_eq!(
+            get_methods(&schema),
+            [HttpMethod::delete, HttpMethod::get, HttpMethod::patch, HttpMethod::post]
+        );
+    }
+
+    #[test]
+    fn unknown_method_order_is_rejected() {
+        assert!(parse_method_order(&String::from("GET,FETCH")).is_err());
+    }
+
+    #[test]
+    fn colliding_paths_fail_with_strict() {
+        let schema: OpenApi = serde_json::from_value(serde_json::json!({
+            "paths": { "/Users": { "get": {} }, "/users": { "get": {} } }
+        }))
+        .unwrap();
+        let get_app = |args: &[&str]| Application {
+            config: Config::from_test_args(args),
+        };
+
+        assert!(get_app(&["--validate-unique-paths"]).check_unique_paths(&schema).is_ok());
+        assert!(get_app(&["--validate-unique-paths", "--strict"])
+            .check_unique_paths(&schema)
+            .is_ok());
+
+        let app = get_app(&["--validate-unique-paths", "--strict", "--path-case", "kebab"]);
+        assert!(matches!(app.check_unique_paths(&schema), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn body_files_are_next_to_request_files() {
+        let schema: OpenApi = serde_json::from_value(serde_json::json!({
+            "paths": {
+                "/users/{id}/pets": {
+                    "get": {},
+                    "post": {
+                        "operationId": "createPet",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "oneOf": [
+                                            { "title": "dog", "type": "object" },
+                                            { "title": "cat", "type": "object" }
+                                        ],
+                                        "discriminator": { "propertyName": "kind" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let generate = |args: &[&str]| {
+            let app = Application {
+                config: Config::from_test_args(args),
+            };
+            let (_, files) = app.generate(&schema).unwrap();
+            return files;
+        };
+        // every `< ./file.json` reference of the request file points to a body file in its folder
+        let get_referenced_paths = |files: &Vec<GeneratedFile>| -> Vec<String> {
+            let request_file = files.iter().find(|file| file.path.ends_with(".http")).unwrap();
+            let folder = request_file.path.rsplit_once('/').unwrap().0;
+            return request_file
+                .content
+                .lines()
+                .filter_map(|line| line.strip_prefix("< ./"))
+                .map(|file_name| format!("{}/{}", folder, file_name))
+                .collect();
+        };
+        let get_body_paths = |files: &Vec<GeneratedFile>| -> Vec<String> {
+            return files
+                .iter()
+                .filter(|file| file.path.ends_with(".json"))
+                .map(|file| file.path.clone())
+                .collect();
+        };
+
+        let files = generate(&["--body-as-file"]);
+        assert_eq!(get_referenced_paths(&files).len(), 1);
+        assert_eq!(get_referenced_paths(&files), get_body_paths(&files));
+
+        let files = generate(&["--body-as-file", "--expand-variants"]);
+        let referenced_paths = get_referenced_paths(&files);
+        assert_eq!(referenced_paths.len(), 2);
+        assert!(referenced_paths[0].ends_with("-dog.json"));
+        assert!(referenced_paths[1].ends_with("-cat.json"));
+        assert_eq!(referenced_paths, get_body_paths(&files));
+    }
+}
@@ -1,12 +1,18 @@
-use crate::open_api::PrimitiveType;
-use std::collections::HashSet;
+use crate::open_api::{ParameterLocation, PrimitiveType};
+use std::collections::BTreeSet;
 
+#[derive(Clone)]
 pub struct Comment {
-    pub possible_types: HashSet<PrimitiveType>,
+    pub possible_types: BTreeSet<PrimitiveType>,
     pub name: String,
     pub required: Option<bool>,
     pub default: Option<String>,
     pub description: Option<String>,
+    pub deprecated: bool,
+    /// The value is always equal to a constant.
+    pub constant: bool,
+    /// Location of the parameter, used to resolve the default of `required`.
+    pub location: Option<ParameterLocation>,
 }
 
 impl Comment {
@@ -27,7 +33,7 @@ impl Comment {
     /// ```
     fn get_formatted(&self) -> String {
         let mut name_with_optional_indicator = self.name.to_owned();
-        if self.required.is_some() && !self.required.unwrap() {
+        if !self.is_required() {
             name_with_optional_indicator.push_str("?");
         }
         let mut comment = format!(
@@ -41,18 +47,45 @@ impl Comment {
                 .join(",")
         );
 
+        if self.constant {
+            comment.push_str(" (const)");
+        }
+
+        if self.deprecated {
+            comment.push_str(" (deprecated)");
+        }
+
         if let Some(desc) = &self.description {
             comment.push_str(&format!(" - {}", desc));
         }
 
         return comment;
     }
+
+    /// Returns whenever the value is required, applying the OpenAPI defaults when it's not set.
+    /// Path parameters default to required, other parameters default to optional.
+    fn is_required(&self) -> bool {
+        match (self.required, &self.location) {
+            (Some(required), _) => required,
+            (None, Some(location)) => *location == ParameterLocation::Path,
+            (None, None) => true,
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct CommentsHolder {
     pub query: Vec<Comment>,
     pub parameters: Vec<Comment>,
     pub body: Vec<Comment>,
+    /// Title of the body schema, used instead of generic "Body" label.
+    pub body_title: Option<String>,
+    /// Body of the request doesn't have to be sent.
+    pub body_optional: bool,
+    /// Description of the request body, appended to the label, e.g. `Body — New customer`.
+    pub body_description: Option<String>,
+    /// URL of the external example of the body, which was not fetched.
+    pub body_external_example: Option<String>,
     pub security: Vec<Comment>,
 }
 
@@ -93,8 +126,20 @@ impl CommentsHolder {
             ));
         }
 
-        if self.body.len() > 0 {
-            output.push(get_formatted_comment(&self.body, &"Body".to_owned()));
+        // optional body always gets the heading, so it's clear why the body is commented out
+        if self.body.len() > 0 || self.body_description.is_some() || self.body_optional {
+            let mut location = self.body_title.clone().unwrap_or("Body".to_owned());
+            if self.body_optional {
+                location.push_str(" (optional)");
+            }
+            if let Some(description) = &self.body_description {
+                location.push_str(&format!(" — {}", description));
+            }
+            output.push(get_formatted_comment(&self.body, &location));
+        }
+
+        if let Some(external_example) = &self.body_external_example {
+            output.push(format!("# Example: {}", external_example));
         }
 
         if self.security.len() > 0 {
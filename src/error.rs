@@ -0,0 +1,52 @@
+use std::fmt;
+
+/// Failures of the application, every kind of failure maps to its own exit code.
+#[derive(Debug)]
+pub enum AppError {
+    /// Usage was printed on request, it's not a failure.
+    Help,
+    /// Arguments are missing or invalid, or the user aborted the run.
+    Usage(String),
+    /// Provided paths can't be used, e.g. the schema file doesn't exist.
+    Config(String),
+    /// Reading or writing of a file failed.
+    Io(String),
+    /// Schema is not a valid JSON or doesn't match the OpenAPI structure.
+    Parse(String),
+    /// Schema or the output doesn't satisfy the request, e.g. the operation was not found.
+    Validation(String),
+    /// External command failed.
+    Command(String),
+    /// Fetching over the network failed, e.g. it timed out.
+    Network(String),
+}
+
+impl AppError {
+    pub fn exit_code(&self) -> exitcode::ExitCode {
+        match self {
+            AppError::Help => exitcode::OK,
+            AppError::Usage(_) => exitcode::USAGE,
+            AppError::Config(_) => exitcode::CONFIG,
+            AppError::Io(_) => exitcode::IOERR,
+            AppError::Parse(_) => exitcode::DATAERR,
+            AppError::Validation(_) => exitcode::DATAERR,
+            AppError::Command(_) => exitcode::SOFTWARE,
+            AppError::Network(_) => exitcode::UNAVAILABLE,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Help => Ok(()),
+            AppError::Usage(message)
+            | AppError::Config(message)
+            | AppError::Validation(message) => write!(f, "{}", message),
+            AppError::Io(message) => write!(f, "IO error: {}", message),
+            AppError::Parse(message) => write!(f, "Invalid schema: {}", message),
+            AppError::Command(message) => write!(f, "Command failed: {}", message),
+            AppError::Network(message) => write!(f, "Network error: {}", message),
+        }
+    }
+}
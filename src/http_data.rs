@@ -1,10 +1,26 @@
 use crate::{
+    app::Config,
     comment::{Comment, CommentsHolder},
-    open_api::{self, Operation, PrimitiveType, Schema},
+    error::AppError,
+    logging::{warn, warn_at},
+    names::Names,
+    open_api::{
+        self, Components, MediaType, Operation, ParameterLocation, Parameters, PrimitiveType, Schema,
+        StatusCode,
+    },
+    output::ENCODE_FUNCTION,
+    reference::fetch_example,
+    skeleton::{create_json_patch_sample, create_skeleton, format_example, PATTERN_KEY},
+    snippet::{create_curl_command, create_snippet, SnippetLanguage},
+};
+use indexmap::IndexMap;
+use serde_json::{to_value, Value};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::Path,
 };
-use std::collections::{HashMap, HashSet};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum HttpMethod {
     GET,
     POST,
@@ -31,13 +47,56 @@ impl ToString for HttpMethod {
     }
 }
 
+/// Request of an alternative security requirement, see `--auth-alternatives`.
+#[derive(Clone)]
+struct AuthAlternative {
+    /// Name of the security scheme, e.g. `apiKey`.
+    scheme: String,
+    auth: Option<String>,
+    cookie: Option<String>,
+    security: Vec<Comment>,
+}
+
+#[derive(Clone)]
 pub struct HttpData {
     method: HttpMethod,
     path: String,
+    query: Vec<String>,
+    /// Optional nullable query parameters, emitted as commented out query lines below the request line.
+    nullable_query: Vec<String>,
     host: String,
     content_type: Option<String>,
+    content_length: Option<String>,
     auth: Option<String>,
+    /// Cookie header with API key & cookie parameters.
+    cookie: Option<String>,
+    /// Prefer header (RFC 7240), when the operation declares it.
+    prefer: Option<String>,
+    /// Headers added to every request from the config.
+    headers: Vec<String>,
+    /// Commented out Idempotency-Key header of unsafe methods.
+    idempotency_key: Option<String>,
+    /// Directives of the JetBrains HTTP client, e.g. `# @no-redirect`.
+    directives: Vec<String>,
+    /// Every query parameter is placed on its own line.
+    multiline_query: bool,
+    /// Name of the request, emitted as `# @name` directive.
+    name: Option<String>,
+    /// Unique names of the requests of the auth alternatives & variants, by the names derived from `name`.
+    derived_names: HashMap<String, String>,
+    /// HTTP version appended to the request line, e.g. `HTTP/1.1`.
+    http_version: Option<String>,
+    /// CORS preflight request is emitted before the request, when it's not a simple request.
+    preflight: bool,
+    /// Equivalent curl command is emitted as a comment above the request.
+    curl_comment: bool,
+    body: Option<String>,
+    /// Discriminator values with bodies of oneOf members, each of them is emitted as its own request.
+    variants: Vec<(String, String)>,
+    /// Other security requirements of the operation, each of them is emitted as its own request.
+    auth_alternatives: Vec<AuthAlternative>,
     comments: CommentsHolder,
+    template: Option<String>,
 }
 
 impl Default for HttpData {
@@ -45,111 +104,92 @@ impl Default for HttpData {
         HttpData {
             method: HttpMethod::GET,
             path: "".to_owned(),
+            query: Vec::new(),
+            nullable_query: Vec::new(),
             host: "host: {{HTTP_HOST}}".to_owned(),
             comments: CommentsHolder {
                 query: Vec::new(),
                 parameters: Vec::new(),
                 body: Vec::new(),
+                body_title: None,
+                body_optional: false,
+                body_description: None,
+                body_external_example: None,
                 security: Vec::new(),
             },
             auth: None,
+            cookie: None,
+            prefer: None,
+            headers: Vec::new(),
+            idempotency_key: None,
+            directives: Vec::new(),
+            multiline_query: false,
+            name: None,
+            derived_names: HashMap::new(),
+            http_version: None,
+            preflight: false,
+            curl_comment: false,
+            body: None,
+            variants: Vec::new(),
+            auth_alternatives: Vec::new(),
+            template: None,
             content_type: None,
+            content_length: None,
         }
     }
 }
 
-fn is_query_param(subs: &str) -> bool {
-    // TODO: More concrete solution
-    return subs.starts_with("{") && subs.contains("}");
-}
-
-#[derive(Clone, Debug)]
-pub struct Names {
-    pub folders: Vec<String>,
-    pub file_path: String,
-    pub file_name: String,
-    pub http_path: String,
-}
-
-impl Names {
-    pub fn new(value: &String) -> Self {
-        let mut splits: Vec<String> = value
-            .split('/')
-            .map(|split| String::from(split.clone()))
-            .collect();
-
-        if splits.len() == 0 {
-            panic!("Invalid endpoint name");
-        }
-
-        // remove query params & empty strings from the splits
-        splits.retain(|split| split.len() > 0 && !is_query_param(split));
-
-        let file_path = format!("/{}", splits.join("/"));
-        let file_name = splits.pop().unwrap().clone();
-        let folders = splits.to_vec().iter().fold(Vec::new(), |mut acc, folder| {
-            if acc.len() == 0 {
-                acc.push(folder.clone());
-            } else {
-                let mut last_folder = acc.last().unwrap().clone();
-                last_folder.push_str("/");
-                last_folder.push_str(folder);
-                acc.push(last_folder.clone());
-            }
-            return acc;
-        });
-
-        return Names {
-            file_name,
-            folders,
-            file_path,
-            http_path: value.clone(),
-        };
-    }
-}
-
 impl HttpData {
     pub fn new(
         names: &Names,
         endpoint_info: &Operation,
         method: &open_api::HttpMethod,
         comps: &Option<open_api::Components>,
-    ) -> Self {
+        config: &Config,
+    ) -> Result<Self, AppError> {
         let mut data: HttpData = Default::default();
 
         // convert raw schema method "get" -> "GET"
         data.method = HttpMethod::from(method.to_owned());
         data.path = names.http_path.to_owned();
+        data.template = config.template.clone();
+        data.headers = config.headers.clone();
+        data.multiline_query = config.multiline_query;
+        data.preflight = config.emit_preflight;
+        data.curl_comment = config.emit_curl_comment;
+        data.http_version = config
+            .http_version
+            .as_ref()
+            .map(|version| format!("HTTP/{}", version));
+        if config.request_names {
+            data.name = Some(names.get_operation_file_name(method, endpoint_info, config));
+        }
+        if config.no_redirect {
+            data.directives.push(String::from("# @no-redirect"));
+        }
+        if config.no_cookie_jar {
+            data.directives.push(String::from("# @no-cookie-jar"));
+        }
+
+        // API key & cookie parameters are merged into a single Cookie header
+        let mut cookies = Vec::new();
 
-        // get auth
-        if let Some(comps) = comps {
+        // get auth, unless it's omitted with `--no-auth`
+        let mut auth_cookies = Vec::new();
+        let mut auth_alternatives = Vec::new();
+        if let Some(comps) = comps.as_ref().filter(|_| !config.no_auth) {
             if let (Some(auth_options), Some(security_schemas)) =
                 (&endpoint_info.security, &comps.security_schemes)
             {
-                let auth = get_auth_schema(auth_options, &security_schemas);
-
-                if let Some(auth) = auth {
-                    match auth {
-                        open_api::SecuritySchema::BearerToken(_) => {
-                            data.auth = Some(String::from("Authorization: Bearer {{TOKEN}}"));
-                        }
-                        open_api::SecuritySchema::ApiKey(api_key) => {
-                            let comment = Comment {
-                                possible_types: HashSet::from([PrimitiveType::String]),
-                                name: api_key.name,
-                                required: Some(true),
-                                default: None,
-                                description: Some(format!(
-                                    "Located in {}",
-                                    &api_key.r#in.to_string()
-                                )),
-                            };
-                            data.comments.security.push(comment);
-                        }
-                        open_api::SecuritySchema::Unknown(_) => {
-                            // not implemented
-                        }
-                    }
+                if let Some(auth) = get_auth_schema(auth_options, &security_schemas) {
+                    let (auth, cookie, mut security) = create_auth(auth, config);
+                    data.auth = auth;
+                    auth_cookies.extend(cookie);
+                    data.comments.security.append(&mut security);
+                }
+                // the first requirement is used by the request itself, see `get_auth_schema`
+                if config.auth_alternatives {
+                    auth_alternatives = get_auth_alternatives(auth_options, &security_schemas);
                 }
             }
         }
@@ -157,17 +197,111 @@ impl HttpData {
         // get parameters
         if let Some(parameters) = &endpoint_info.parameters {
             for params in parameters {
+                let deprecated = params.deprecated.unwrap_or(false);
+                // path parameters are always required, regardless of the schema
+                // ref: https://spec.openapis.org/oas/v3.1.0#parameter-object
+                let required = match params.r#in {
+                    ParameterLocation::Path => Some(true),
+                    _ => params.required,
+                };
+                // parameters described by content note their media type, e.g. JSON in the query
+                let (possible_types, description) = match params.get_content() {
+                    Some((content_type, media_type)) => (
+                        media_type
+                            .schema
+                            .as_ref()
+                            .map_or(BTreeSet::from([PrimitiveType::Object]), |schema| {
+                                schema.get_all_types()
+                            }),
+                        Some(format!("Serialized as {}", content_type)),
+                    ),
+                    None => (BTreeSet::from([PrimitiveType::String]), None),
+                };
                 let comment = Comment {
-                    possible_types: HashSet::from([PrimitiveType::String]),
+                    possible_types,
                     name: params.name.clone(),
-                    required: params.required,
-                    default: params.default.clone(),
-                    description: None,
+                    required,
+                    default: params.get_default(),
+                    description,
+                    deprecated,
+                    constant: params.get_const().is_some(),
+                    location: Some(params.r#in.clone()),
                 };
 
-                match params.r#in.as_ref() {
-                    "query" => data.comments.query.push(comment),
-                    "path" => data.comments.parameters.push(comment),
+                match params.r#in {
+                    ParameterLocation::Query => {
+                        // empty value of optional nullable param is ambiguous (null or empty string),
+                        // so it's commented out and the user uncomments it on purpose
+                        if params.is_nullable() && required != Some(true) {
+                            if !(deprecated && config.exclude_deprecated) {
+                                data.nullable_query.push(format!(
+                                    "{}={}",
+                                    params.name,
+                                    get_parameter_value(params, config).unwrap_or_default()
+                                ));
+                            }
+                            let note = String::from("Nullable, commented out in the query");
+                            data.comments.query.push(Comment {
+                                description: Some(match comment.description {
+                                    Some(description) => format!("{}. {}", description, note),
+                                    None => note,
+                                }),
+                                ..comment
+                            });
+                            continue;
+                        }
+
+                        // deprecated params are still listed in the comments,
+                        // so the user knows they exist
+                        if !(deprecated && config.exclude_deprecated) {
+                            data.query.push(format!(
+                                "{}={}",
+                                params.name,
+                                get_parameter_value(params, config).unwrap_or_default()
+                            ));
+                        }
+                        data.comments.query.push(comment);
+                    }
+                    ParameterLocation::Path => {
+                        let value = get_parameter_value(params, config);
+                        let value = if config.encode_path_params {
+                            Some(encode_path_value(value, &params.name))
+                        } else {
+                            value
+                        };
+                        if let Some(value) = value {
+                            data.path = data
+                                .path
+                                .replace(&format!("{{{}}}", params.name), &value);
+                        }
+                        data.comments.parameters.push(comment);
+                    }
+                    ParameterLocation::Cookie => {
+                        if !(deprecated && config.exclude_deprecated) {
+                            cookies.push(format!(
+                                "{}={}",
+                                params.name,
+                                get_parameter_value(params, config).unwrap_or_default()
+                            ));
+                        }
+                        data.comments.parameters.push(comment);
+                    }
+                    // ref: https://www.rfc-editor.org/rfc/rfc7240
+                    ParameterLocation::Header if params.name.eq_ignore_ascii_case("prefer") => {
+                        let allowed_values = params.get_enum();
+                        if !(deprecated && config.exclude_deprecated) {
+                            let value = get_parameter_value(params, config)
+                                .or(allowed_values.first().cloned())
+                                .unwrap_or_default();
+                            data.prefer = Some(format!("{}: {}", params.name, value));
+                        }
+                        data.comments.parameters.push(Comment {
+                            description: Some(format!("Allowed: {}", allowed_values.join(", ")))
+                                .filter(|_| allowed_values.len() > 0)
+                                .or(comment.description),
+                            ..comment
+                        });
+                    }
                     _ => (),
                 }
             }
@@ -176,103 +310,1034 @@ impl HttpData {
         // TODO: Handle those properly
         // get body
         if let Some(body) = &endpoint_info.request_body {
-            for (content, value) in &body.content {
-                match content.as_ref() {
-                    "application/json" => {
-                        // TODO: place it somewhere else
-                        data.content_type = Some(String::from("Content-Type: application/json"));
-                        if let Some(schema) = &value.schema {
-                            match schema {
-                                Schema::Object(obj) => data.comments.body.append(
-                                    &mut create_comment_from_props(&obj.properties, &obj.required),
-                                ),
-
-                                Schema::AllOf { allOf } => {
-                                    for obj in allOf {
-                                        data.comments.body.append(&mut create_comment_from_props(
-                                            &obj.properties,
-                                            &obj.required,
-                                        ));
-                                    }
-                                }
-                                Schema::AnyOf { anyOf } => {
-                                    for obj in anyOf {
-                                        data.comments.body.append(&mut create_comment_from_props(
-                                            &obj.properties,
-                                            &obj.required,
-                                        ));
-                                    }
-                                }
-                                Schema::OneOf { oneOf } => {
-                                    for obj in oneOf {
-                                        data.comments.body.append(&mut create_comment_from_props(
-                                            &obj.properties,
-                                            &obj.required,
-                                        ));
-                                    }
-                                }
-                                Schema::Not { not } => {
-                                    for obj in not {
-                                        data.comments.body.append(&mut create_comment_from_props(
-                                            &obj.properties,
-                                            &obj.required,
-                                        ));
-                                    }
-                                }
+            if let Some((content, value)) = select_media_type(&body.content, config) {
+                // TODO: place it somewhere else
+                data.content_type = Some(format!("Content-Type: {}", content));
+                // external example is only referenced, unless it's fetched with `--fetch-examples`
+                let external_value = value.get_external_value().filter(|_| value.get_example().is_none());
+                let external_example = match external_value {
+                    Some(location) if config.fetch_examples => {
+                        match fetch_example(
+                            location,
+                            Path::new(&config.file_path),
+                            config.fetch_timeout,
+                        ) {
+                            Ok(example) => Some(example),
+                            // timeout aborts the run, the server would most likely time out again
+                            Err(err @ AppError::Network(_)) => return Err(err),
+                            Err(err) => {
+                                warn_at(
+                                    &format!("Example {} could not be fetched: {}", location, err),
+                                    &[("path", &names.http_path)],
+                                );
+                                None
                             }
                         }
                     }
-                    _ => (),
+                    _ => None,
+                };
+                if external_example.is_none() {
+                    data.comments.body_external_example = external_value.cloned();
+                }
+                let example = value.get_example().or(external_example.as_ref());
+                // with `--example-from-response`, body without any example reuses example of the response
+                let response_example = if config.example_from_response
+                    && example.is_none()
+                    && value
+                        .schema
+                        .as_ref()
+                        .is_none_or(|schema| schema.get_example().is_none())
+                {
+                    get_response_example(endpoint_info, config)
+                } else {
+                    None
+                };
+                // sample is created only for JSON bodies,
+                // example of the media type is preferred over the skeleton of the schema
+                let is_json_patch = get_essence(content) == JSON_PATCH_MEDIA_TYPE;
+                let sample = match example.or(response_example.as_ref()) {
+                    _ if !is_json_media_type(content) => None,
+                    Some(example) => Some(format_example(example, config)),
+                    // schema describes the patch operations, not the patched resource
+                    None if is_json_patch => Some(create_json_patch_sample(config)),
+                    None => value
+                        .schema
+                        .as_ref()
+                        .map(|schema| create_skeleton(schema, config)),
+                };
+                let is_skeleton = example.or(response_example.as_ref()).is_none();
+                if let Some(schema) = value.schema.as_ref() {
+                    if config.expand_variants
+                        && is_skeleton
+                        && is_json_media_type(content)
+                        && !is_json_patch
+                    {
+                        data.variants = create_variants(schema, comps, &names.http_path, config);
+                    }
+                }
+                // the first variant stands for the request, e.g. in snippets
+                let sample = data.variants.first().map(|(_, body)| body.clone()).or(sample);
+                data.body = sample;
+                data.comments.body_optional = body.required == Some(false);
+                // description can span more lines, the comment is kept on a single one
+                data.comments.body_description = body
+                    .description
+                    .as_ref()
+                    .map(|description| description.split_whitespace().collect::<Vec<&str>>().join(" "))
+                    .filter(|description| description.len() > 0);
+                if let Some(schema) = &value.schema {
+                    if let Schema::Object(obj) = schema {
+                        data.comments.body_title = obj.title.clone();
+                    }
+                    let mut comments = create_comment_from_schema(schema);
+                    // merge patch sends only the changed properties, so none of them is required
+                    if get_essence(content) == MERGE_PATCH_MEDIA_TYPE {
+                        for comment in &mut comments {
+                            comment.required = Some(false);
+                        }
+                    }
+                    data.comments.body.append(&mut comments);
+                }
+            }
+        }
+
+        // API key of the auth goes first, the cookie parameters follow
+        data.cookie = get_cookie_header(&auth_cookies, &cookies);
+        for (scheme, auth) in auth_alternatives {
+            let (auth, cookie, security) = create_auth(auth, config);
+            data.auth_alternatives.push(AuthAlternative {
+                scheme,
+                auth,
+                cookie: get_cookie_header(&Vec::from_iter(cookie), &cookies),
+                security,
+            });
+        }
+
+        if config.sort_properties {
+            data.comments.body.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        // no body was declared, some servers require it to be stated explicitly
+        if data.body.is_none() && config.explicit_empty_body {
+            match data.method {
+                HttpMethod::POST | HttpMethod::PUT | HttpMethod::PATCH => {
+                    data.content_length = Some(String::from("Content-Length: 0"));
+                }
+                _ => (),
+            }
+        }
+
+        // commented out, so the same key is sent only on purpose, e.g. when retrying the request
+        if config.emit_idempotency_key {
+            match data.method {
+                HttpMethod::POST | HttpMethod::PUT | HttpMethod::PATCH | HttpMethod::DELETE => {
+                    data.idempotency_key =
+                        Some(String::from("# Idempotency-Key: {{IDEMPOTENCY_KEY}}"));
                 }
+                _ => (),
             }
         }
 
-        return data;
+        return Ok(data);
     }
 
-    /// Converts HttpData struct to formatted string
+    /// Converts HttpData struct to formatted string.
+    /// With `--expand-variants`, every variant is a request of its own, named after its discriminator value.
     pub fn get_formatted(&self) -> String {
+        let mut requests = Vec::from([self.get_formatted_variants(self.name.as_ref())]);
+        for alternative in &self.auth_alternatives {
+            let mut data = self.clone();
+            data.auth = alternative.auth.clone();
+            data.cookie = alternative.cookie.clone();
+            data.comments.security = alternative.security.clone();
+            let name = self.name.as_ref().map(|name| format!("{}-{}", name, alternative.scheme));
+            requests.push(data.get_formatted_variants(name.as_ref()));
+        }
+        return requests.join("\n\n###\n\n");
+    }
+
+    /// Formats the request, with `--expand-variants` every variant of the body is formatted as its own request.
+    /// Name derived from the name of the request is replaced with its unique name, see `make_name_unique`.
+    fn get_formatted_variants(&self, name: Option<&String>) -> String {
+        if self.variants.is_empty() {
+            let name = name.map(|name| self.get_derived_name(name));
+            return self.get_formatted_request(name.as_ref(), self.body.as_ref(), true);
+        }
+
+        return self
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, (value, body))| {
+                let name = name.map(|name| self.get_derived_name(&format!("{}-{}", name, value)));
+                // preflight is same for all the variants
+                self.get_formatted_request(name.as_ref(), Some(body), i == 0)
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n###\n\n");
+    }
+
+    fn get_formatted_request(
+        &self,
+        name: Option<&String>,
+        body: Option<&String>,
+        with_preflight: bool,
+    ) -> String {
         let mut output: Vec<&str> = Vec::new();
 
+        // PREFLIGHT
+        let preflight = self.get_preflight().filter(|_| with_preflight);
+        if let Some(preflight) = &preflight {
+            output.push(preflight);
+            output.push("");
+        }
+
+        // DIRECTIVES
+        let name = name.map(|name| format!("# @name {}", name));
+        output.extend(name.iter().map(|name| name.as_str()));
+        output.extend(self.directives.iter().map(|directive| directive.as_str()));
+
+        if let Some(template) = &self.template {
+            // blank lines of the template are kept, the user placed them on purpose
+            let formatted = self.get_formatted_from_template(template, body);
+            output.push(&formatted);
+            return output.join("\n");
+        }
+
         // COMMENTS
         let comments = self.comments.get_formatted();
         if comments.len() > 0 {
             output.push(&comments);
         }
 
+        // CURL
+        let curl = self.get_curl_comment(body);
+        if let Some(curl) = &curl {
+            output.push(curl);
+        }
+
         // METHOD & PATH
-        let path_and_method = format!("{} {}", self.method.to_string(), self.path);
+        let mut path_and_method =
+            format!("{} {}", self.method.to_string(), self.get_formatted_path());
+        if let Some(http_version) = &self.http_version {
+            path_and_method.push_str(&format!(" {}", http_version));
+        }
         output.push(&path_and_method);
 
+        // NULLABLE QUERY
+        let nullable_query = self.get_nullable_query();
+        if let Some(nullable_query) = &nullable_query {
+            output.push(nullable_query);
+        }
+
         // HOST
         output.push(&self.host);
 
+        // HEADERS
+        let headers = self.get_formatted_headers();
+        output.extend(headers.iter().map(|header| header.as_str()));
+
+        // BODY
+        let body = self.get_formatted_body(body);
+        if let Some(body) = &body {
+            output.push("");
+            output.push(body);
+        }
+
+        return trim_blank_lines(&output.join("\n"));
+    }
+
+    /// Renders the request with user provided template,
+    /// `{{method}}`, `{{path}}`, `{{host}}`, `{{headers}}`, `{{body}}` & `{{comments}}` are replaced
+    /// with the request data, other placeholders are kept as they are.
+    /// Line with only a placeholder without any data, e.g. `{{body}}` of GET request, is left out,
+    /// other lines of the template are kept as they are.
+    fn get_formatted_from_template(&self, template: &String, body: Option<&String>) -> String {
+        let headers = self
+            .get_formatted_headers()
+            .iter()
+            .map(|header| header.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        let path = match self.get_nullable_query() {
+            Some(nullable_query) => format!("{}\n{}", self.get_formatted_path(), nullable_query),
+            None => self.get_formatted_path(),
+        };
+
+        let placeholders = [
+            ("{{method}}", self.method.to_string()),
+            ("{{path}}", path),
+            ("{{host}}", self.host.clone()),
+            ("{{headers}}", headers),
+            ("{{body}}", self.get_formatted_body(body).unwrap_or_default()),
+            ("{{comments}}", self.comments.get_formatted()),
+        ];
+
+        let mut lines = Vec::new();
+        for line in template.lines() {
+            let is_empty_placeholder = placeholders
+                .iter()
+                .any(|(placeholder, value)| line.trim() == *placeholder && value.is_empty());
+            if is_empty_placeholder {
+                continue;
+            }
+            let mut line = line.to_owned();
+            for (placeholder, value) in &placeholders {
+                line = line.replace(placeholder, value);
+            }
+            lines.push(line);
+        }
+        return lines.join("\n");
+    }
+
+    /// Returns CORS preflight request of the request with `--emit-preflight`, when the request is not simple,
+    /// i.e. it uses other method than GET & POST, or headers which are not safelisted, e.g. JSON content type.
+    /// ref: https://fetch.spec.whatwg.org/#cors-preflight-fetch
+    fn get_preflight(&self) -> Option<String> {
+        if !self.preflight {
+            return None;
+        }
+
+        let mut request_headers: Vec<String> = self
+            .get_headers()
+            .iter()
+            .filter_map(|header| header.split_once(':'))
+            .filter(|(name, value)| !is_safelisted_header(name.trim(), value.trim()))
+            .map(|(name, _)| name.trim().to_lowercase())
+            .collect();
+        request_headers.sort();
+        request_headers.dedup();
+
+        let is_simple_method = matches!(self.method, HttpMethod::GET | HttpMethod::POST);
+        if is_simple_method && request_headers.is_empty() {
+            return None;
+        }
+
+        let mut lines = Vec::from([
+            format!("OPTIONS {}", self.get_path()),
+            self.host.clone(),
+            String::from("Origin: {{ORIGIN}}"),
+            format!("Access-Control-Request-Method: {}", self.method.to_string()),
+        ]);
+        if request_headers.len() > 0 {
+            lines.push(format!(
+                "Access-Control-Request-Headers: {}",
+                request_headers.join(", ")
+            ));
+        }
+        return Some(lines.join("\n"));
+    }
+
+    /// Returns body of the request, optional body is commented out, so it's sent only on purpose.
+    fn get_formatted_body(&self, body: Option<&String>) -> Option<String> {
+        let body = body?;
+        if !self.comments.body_optional {
+            return Some(body.clone());
+        }
+        return Some(
+            body.lines()
+                .map(|line| format!("# {}", line))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        );
+    }
+
+    /// Checks whenever the request of the operation gets a body, without creating the request,
+    /// e.g. to count files of `--body-as-file` upfront.
+    /// External examples count only with `--fetch-examples`, as in `HttpData::new`, even if they can't be fetched.
+    pub fn has_body(endpoint_info: &Operation, config: &Config) -> bool {
+        let (content, value) = match endpoint_info
+            .request_body
+            .as_ref()
+            .and_then(|body| select_media_type(&body.content, config))
+        {
+            Some(selected) => selected,
+            None => return false,
+        };
+
+        return is_json_media_type(content)
+            && (value.get_example().is_some()
+                || (config.fetch_examples && value.get_external_value().is_some())
+                || value.schema.is_some()
+                || get_essence(content) == JSON_PATCH_MEDIA_TYPE
+                || (config.example_from_response
+                    && get_response_example(endpoint_info, config).is_some()));
+    }
+
+    /// Counts files of the request body with `--body-as-file`, without creating the request,
+    /// i.e. one for the body, or one for every variant of the body with `--expand-variants`.
+    pub fn get_body_files_count(
+        endpoint_info: &Operation,
+        comps: &Option<Components>,
+        config: &Config,
+    ) -> usize {
+        if !HttpData::has_body(endpoint_info, config) {
+            return 0;
+        }
+        let (content, value) = match endpoint_info
+            .request_body
+            .as_ref()
+            .and_then(|body| select_media_type(&body.content, config))
+        {
+            Some(selected) => selected,
+            None => return 0,
+        };
+
+        // same conditions as of the variants in `HttpData::new`, variants replace only the skeleton
+        let has_example = value.get_example().is_some()
+            || (config.fetch_examples && value.get_external_value().is_some())
+            || (config.example_from_response
+                && value
+                    .schema
+                    .as_ref()
+                    .is_none_or(|schema| schema.get_example().is_none())
+                && get_response_example(endpoint_info, config).is_some());
+        let variants_count = match &value.schema {
+            Some(schema)
+                if config.expand_variants
+                    && !has_example
+                    && get_essence(content) != JSON_PATCH_MEDIA_TYPE =>
+            {
+                get_variant_values(schema, comps)
+                    .iter()
+                    .filter(|(_, value)| value.is_some())
+                    .count()
+            }
+            _ => 0,
+        };
+        return variants_count.max(1);
+    }
+
+    /// Moves body of the request into a file next to the request file,
+    /// the request references it with `< ./file_name` instead.
+    /// With `--expand-variants`, body of every variant is moved into its own file,
+    /// `get_file_name` gets the discriminator value of the variant.
+    /// Returns names of the files with the original bodies, empty when the request has no body.
+    pub fn move_body_to_file(
+        &mut self,
+        get_file_name: &mut dyn FnMut(Option<&String>) -> String,
+    ) -> Vec<(String, String)> {
+        if self.variants.is_empty() {
+            let body = match self.body.take() {
+                Some(body) => body,
+                None => return Vec::new(),
+            };
+            let file_name = get_file_name(None);
+            self.body = Some(format!("< ./{}", file_name));
+            return Vec::from([(file_name, body)]);
+        }
+
+        let mut files = Vec::new();
+        for (value, body) in &mut self.variants {
+            let file_name = get_file_name(Some(value));
+            let reference = format!("< ./{}", file_name);
+            files.push((file_name, std::mem::replace(body, reference)));
+        }
+        // the first variant stands for the request, as in `HttpData::new`
+        self.body = self.variants.first().map(|(_, body)| body.clone());
+        return files;
+    }
+
+    /// Makes name of the request unique among names already used in the same file,
+    /// by adding the method, or a number when even that is already used.
+    /// Names of the auth alternatives & variants derived from it are made unique too, by a number.
+    pub fn make_name_unique(&mut self, used_names: &mut HashSet<String>) {
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => return,
+        };
+
+        let method = self.method.to_string().to_lowercase();
+        let unique_name = get_unique_name(&name, Some(&method), used_names);
+        self.derived_names = self
+            .get_derived_names(&unique_name)
+            .into_iter()
+            .map(|derived| {
+                let unique_derived = get_unique_name(&derived, None, used_names);
+                return (derived, unique_derived);
+            })
+            .collect();
+        self.name = Some(unique_name);
+    }
+
+    /// Returns names of the requests of the auth alternatives & variants, as `get_formatted` derives them from the name.
+    fn get_derived_names(&self, name: &String) -> Vec<String> {
+        let mut names = Vec::new();
+        let alternative_names = self
+            .auth_alternatives
+            .iter()
+            .map(|alternative| format!("{}-{}", name, alternative.scheme));
+        for (i, prefix) in std::iter::once(name.clone()).chain(alternative_names).enumerate() {
+            if self.variants.is_empty() {
+                // name of the request itself is already unique
+                if i > 0 {
+                    names.push(prefix);
+                }
+                continue;
+            }
+            names.extend(self.variants.iter().map(|(value, _)| format!("{}-{}", prefix, value)));
+        }
+        return names;
+    }
+
+    /// Returns unique name of the name derived from the name of the request, see `make_name_unique`.
+    fn get_derived_name(&self, name: &String) -> String {
+        return self.derived_names.get(name).unwrap_or(name).clone();
+    }
+
+    /// Creates code calling the request in the language, see `--snippet`.
+    /// Optional body is left out, as it's commented out in the request file too.
+    pub fn get_snippet(&self, language: &SnippetLanguage) -> String {
+        let body = self.body.as_ref().filter(|_| !self.comments.body_optional);
+
+        return create_snippet(
+            language,
+            &self.get_request_line(),
+            &self.method.to_string(),
+            &self.get_url(),
+            &self.get_header_pairs(),
+            body,
+        );
+    }
+
+    /// Returns equivalent curl command of the request as a comment with `--emit-curl-comment`,
+    /// e.g. `# curl -X GET '{{HTTP_HOST}}/customers'`. Optional body is left out, as in the snippets.
+    fn get_curl_comment(&self, body: Option<&String>) -> Option<String> {
+        if !self.curl_comment {
+            return None;
+        }
+
+        let body = body.filter(|_| !self.comments.body_optional);
+        let command = create_curl_command(
+            &self.method.to_string(),
+            &self.get_url(),
+            &self.get_header_pairs(),
+            body,
+        );
+        return Some(format!("# {}", command));
+    }
+
+    /// Returns URL of the request, i.e. the host followed by the path, e.g. `{{HTTP_HOST}}/customers`.
+    fn get_url(&self) -> String {
+        let host = self.host.trim_start_matches("host:").trim();
+        return format!("{}{}", host, self.get_path());
+    }
+
+    /// Returns name & value of every header of the request.
+    fn get_header_pairs(&self) -> Vec<(String, String)> {
+        return self
+            .get_headers()
+            .iter()
+            .filter_map(|header| header.split_once(':'))
+            .map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+            .collect();
+    }
+
+    /// Returns method & path of the request, e.g. `GET /customers?limit=10`.
+    pub fn get_request_line(&self) -> String {
+        return format!("{} {}", self.method.to_string(), self.get_path());
+    }
+
+    /// Returns path of the request, including the query string.
+    /// With `--multiline-query`, every query parameter is placed on its own indented line, e.g.
+    ///
+    /// ```text
+    /// GET /customers
+    ///     ?limit=10
+    ///     &offset=0
+    /// ```
+    fn get_formatted_path(&self) -> String {
+        if !self.multiline_query || self.query.len() == 0 {
+            return self.get_path();
+        }
+
+        let mut lines = Vec::from([self.path.clone()]);
+        for (i, param) in self.query.iter().enumerate() {
+            let separator = if i == 0 { "?" } else { "&" };
+            lines.push(format!("    {}{}", separator, param));
+        }
+        return lines.join("\n");
+    }
+
+    /// Returns commented out lines of the optional nullable query parameters, they continue the query, e.g.
+    ///
+    /// ```text
+    /// GET /customers?limit=10
+    /// #   &cursor=
+    /// ```
+    fn get_nullable_query(&self) -> Option<String> {
+        if self.nullable_query.len() == 0 {
+            return None;
+        }
+
+        let lines = self.nullable_query.iter().enumerate().map(|(i, param)| {
+            let separator = if i == 0 && self.query.len() == 0 { "?" } else { "&" };
+            return format!("#   {}{}", separator, param);
+        });
+        return Some(lines.collect::<Vec<String>>().join("\n"));
+    }
+
+    /// Returns path of the request, including the query string.
+    fn get_path(&self) -> String {
+        let mut path = self.path.clone();
+        if self.query.len() > 0 {
+            path.push_str(&format!("?{}", self.query.join("&")));
+        }
+        return path;
+    }
+
+    /// Returns all headers of the request, except the host.
+    fn get_headers(&self) -> Vec<&String> {
+        let mut headers = Vec::new();
+
         // CONTENT-TYPE
         if let Some(content_type) = &self.content_type {
-            output.push(content_type);
+            headers.push(content_type);
+        }
+
+        // CONTENT-LENGTH
+        if let Some(content_length) = &self.content_length {
+            headers.push(content_length);
         }
 
         // AUTH
         if let Some(auth) = &self.auth {
-            output.push(auth);
+            headers.push(auth);
+        }
+
+        // COOKIE
+        if let Some(cookie) = &self.cookie {
+            headers.push(cookie);
+        }
+
+        // PREFER
+        if let Some(prefer) = &self.prefer {
+            headers.push(prefer);
         }
 
-        return output.join("\n");
+        // DEFAULT HEADERS
+        headers.extend(self.headers.iter());
+
+        return headers;
+    }
+
+    /// Returns header lines of the request file, i.e. all headers with the commented out ones,
+    /// which are not sent, so they are left out of the snippets & the preflight.
+    fn get_formatted_headers(&self) -> Vec<&String> {
+        let mut headers = self.get_headers();
+        headers.extend(self.idempotency_key.iter());
+        return headers;
+    }
+}
+
+/// Removes trailing whitespace of the lines, blank lines at the start & the end,
+/// and collapses repeated blank lines into one, e.g. left by empty sections of the request.
+fn trim_blank_lines(value: &String) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in value.lines().map(|line| line.trim_end()) {
+        let is_repeated_blank = line.is_empty() && lines.last().is_none_or(|last| last.is_empty());
+        if !is_repeated_blank {
+            lines.push(line);
+        }
+    }
+    if lines.last() == Some(&"") {
+        lines.pop();
+    }
+    return lines.join("\n");
+}
+
+/// Returns the name, or the name with the suffix, e.g. the method, or with a number when it's already used.
+/// The returned name is added to the used names.
+fn get_unique_name(
+    name: &String,
+    suffix: Option<&String>,
+    used_names: &mut HashSet<String>,
+) -> String {
+    let mut unique_name = name.clone();
+    if let Some(suffix) = suffix.filter(|_| used_names.contains(&unique_name)) {
+        unique_name = format!("{}-{}", name, suffix);
+    }
+    let mut number = 1;
+    while used_names.contains(&unique_name) {
+        number += 1;
+        unique_name = format!("{}-{}", name, number);
+    }
+
+    used_names.insert(unique_name.clone());
+    return unique_name;
+}
+
+/// Checks whenever the header can be sent cross-origin without a preflight request,
+/// cookies are set by the browser itself, so they don't need one either.
+/// ref: https://fetch.spec.whatwg.org/#cors-safelisted-request-header
+fn is_safelisted_header(name: &str, value: &str) -> bool {
+    match name.to_lowercase().as_str() {
+        "accept" | "accept-language" | "content-language" | "cookie" => true,
+        "content-type" => ["application/x-www-form-urlencoded", "multipart/form-data", "text/plain"]
+            .iter()
+            .any(|content_type| matches_content_type(&value.to_owned(), content_type)),
+        _ => false,
+    }
+}
+
+/// Percent-encodes path parameter value, variables & missing values via the client, e.g. `{{encodeURIComponent(id)}}`.
+fn encode_path_value(value: Option<String>, name: &String) -> String {
+    let value = match value {
+        Some(value) if !(value.starts_with("{{") && value.ends_with("}}")) => {
+            return percent_encode(&value);
+        }
+        Some(value) => value.trim_start_matches("{{").trim_end_matches("}}").trim().to_owned(),
+        None => name.clone(),
+    };
+    return format!("{{{{{}({})}}}}", ENCODE_FUNCTION, value);
+}
+
+/// Percent-encodes all the characters except the unreserved ones.
+/// ref: https://www.rfc-editor.org/rfc/rfc3986#section-2.3
+fn percent_encode(value: &String) -> String {
+    let mut output = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            output.push(byte as char);
+        } else {
+            output.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    return output;
+}
+
+/// Creates body of every oneOf member with discriminator, with the discriminator property filled, see `--expand-variants`.
+/// Members without discriminator value are skipped.
+fn create_variants(
+    schema: &Schema,
+    comps: &Option<Components>,
+    path: &String,
+    config: &Config,
+) -> Vec<(String, String)> {
+    let property_name = match schema {
+        Schema::OneOf {
+            discriminator: Some(discriminator),
+            ..
+        } => &discriminator.property_name,
+        _ => return Vec::new(),
+    };
+
+    let mut variants = Vec::new();
+    for (i, (member, value)) in get_variant_values(schema, comps).into_iter().enumerate() {
+        match value {
+            Some(value) => {
+                let member = member.with_const_property(property_name, &value);
+                variants.push((value, create_skeleton(&member, config)));
+            }
+            None => warn_at(
+                &format!(
+                    "oneOf member {} of {} has no value of {}, skipping",
+                    i + 1,
+                    path,
+                    property_name
+                ),
+                &[("path", path)],
+            ),
+        }
+    }
+
+    return variants;
+}
+
+/// Returns every oneOf member of the schema with discriminator, with its discriminator value.
+/// Discriminator value of the member is its const of the property, key of the mapping or name of the component schema
+/// equal to the member, or its title.
+fn get_variant_values<'a>(
+    schema: &'a Schema,
+    comps: &Option<Components>,
+) -> Vec<(&'a Schema, Option<String>)> {
+    let (members, discriminator) = match schema {
+        Schema::OneOf {
+            oneOf: members,
+            discriminator: Some(discriminator),
+        } => (members, discriminator),
+        _ => return Vec::new(),
+    };
+
+    // members are already resolved, so they are compared with the resolved component schemas
+    let component_schemas: Vec<(&String, &Value)> = comps
+        .iter()
+        .flat_map(|comps| comps.parsed_schemas.iter())
+        .collect();
+    // mapping references the schemas, e.g. `#/components/schemas/Dog`, unmapped schemas use their name
+    let mut values: Vec<(&str, &str)> = Vec::new();
+    if let Some(mapping) = &discriminator.mapping {
+        for (value, reference) in mapping {
+            values.push((value, reference.rsplit('/').next().unwrap_or_default()));
+        }
+    }
+    values.extend(component_schemas.iter().map(|(name, _)| (name.as_str(), name.as_str())));
+
+    let mut variants = Vec::new();
+    for member in members {
+        let member_value = to_value(member).ok();
+        let is_member = |name: &str| {
+            return component_schemas.iter().any(|(schema_name, schema)| {
+                schema_name.as_str() == name && Some(*schema) == member_value.as_ref()
+            });
+        };
+        let value = member
+            .get_property_const(&discriminator.property_name)
+            .or_else(|| {
+                values
+                    .iter()
+                    .find(|(_, name)| is_member(name))
+                    .map(|(value, _)| value.to_string())
+            })
+            .or_else(|| member.get_title().cloned());
+        variants.push((member, value));
+    }
+
+    return variants;
+}
+
+/// Returns value which should pre-fill the parameter,
+/// constant is preferred over the example, which is preferred over the default.
+/// With `--redact`, examples of sensitive parameters are replaced with a variable.
+/// Parameters described by content are filled with compact JSON of their example, or an empty JSON value.
+fn get_parameter_value(params: &Parameters, config: &Config) -> Option<String> {
+    if let Some((_, media_type)) = params.get_content() {
+        if let Some(example) = media_type.get_example() {
+            return Some(example.to_string());
+        }
+        let is_array = media_type.schema.as_ref().is_some_and(|schema| {
+            schema.get_all_types().contains(&PrimitiveType::Array)
+        });
+        return Some(String::from(if is_array { "[]" } else { "{}" }));
+    }
+
+    if let Some(constant) = params.get_const() {
+        return Some(constant);
+    }
+    if let Some(example) = params.get_example() {
+        if config.should_redact(&params.name) {
+            return Some(format!("{{{{{}}}}}", params.name));
+        }
+        return Some(example);
+    }
+    return params.get_default();
+}
+
+/// Returns JSON example of the first successful (2xx) response, without its readOnly & writeOnly properties.
+/// readOnly properties are not sent in requests and writeOnly properties, e.g. `password`, are never returned.
+/// With `--unwrap`, content of the wrapper property is returned, e.g. `{ "data": { ... } }` -> `{ ... }`.
+fn get_response_example(endpoint_info: &Operation, config: &Config) -> Option<Value> {
+    let responses = endpoint_info.responses.as_ref()?;
+    // exact codes are preferred over the 2XX range
+    let mut codes: Vec<&StatusCode> = responses.keys().filter(|code| code.is_success()).collect();
+    codes.sort();
+
+    for code in codes {
+        let content = match &responses[code].content {
+            Some(content) => content,
+            None => continue,
+        };
+
+        for (content_type, media_type) in content {
+            if !is_json_media_type(content_type) {
+                continue;
+            }
+
+            let example = media_type
+                .get("examples")
+                .and_then(|examples| examples.as_object())
+                .and_then(|examples| examples.values().find_map(|example| example.get("value")))
+                .or(media_type.get("example"))
+                .or(media_type.pointer("/schema/examples/0"))
+                .or(media_type.pointer("/schema/example"));
+
+            if let Some(example) = example {
+                let mut example = example.clone();
+                let mut schema = media_type.get("schema");
+                if let Some(wrapper) = &config.unwrap {
+                    if let Some(content) = example.get(wrapper) {
+                        example = content.clone();
+                        schema = schema.and_then(|schema| {
+                            schema.pointer(&format!("/properties/{}", wrapper))
+                        });
+                    }
+                }
+                if let Some(schema) = schema {
+                    remove_marked_properties(&mut example, schema, &["readOnly", "writeOnly"]);
+                }
+                return Some(example);
+            }
+        }
+    }
+
+    return None;
+}
+
+/// Removes properties marked with any of the keywords in the schema, e.g. `readOnly`, from the example,
+/// nested objects & arrays included.
+fn remove_marked_properties(example: &mut Value, schema: &Value, keywords: &[&str]) {
+    if let Some(members) = schema.get("allOf").and_then(|members| members.as_array()) {
+        for member in members {
+            remove_marked_properties(example, member, keywords);
+        }
+    }
+
+    match example {
+        Value::Object(map) => {
+            let properties = match schema.get("properties").and_then(|props| props.as_object()) {
+                Some(properties) => properties,
+                None => return,
+            };
+            // retain keeps the order of the remaining properties
+            map.retain(|name, _| {
+                let property = properties.get(name);
+                !keywords.iter().any(|keyword| {
+                    property.and_then(|property| property.get(keyword)) == Some(&Value::Bool(true))
+                })
+            });
+            for (name, value) in map.iter_mut() {
+                if let Some(property) = properties.get(name) {
+                    remove_marked_properties(value, property, keywords);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(items_schema) = schema.get("items") {
+                for item in items {
+                    remove_marked_properties(item, items_schema, keywords);
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Selects media type of the request body by its content type,
+/// content types preferred by the user are checked first, then json > form > multipart > xml,
+/// otherwise the first declared media type is used.
+fn select_media_type<'a>(
+    content: &'a IndexMap<String, MediaType>,
+    config: &Config,
+) -> Option<(&'a String, &'a MediaType)> {
+    for preferred in &config.preferred_content_types {
+        let selected = content
+            .iter()
+            .find(|(content_type, _)| matches_content_type(content_type, preferred));
+        if selected.is_some() {
+            return selected;
+        }
+    }
+
+    let priority: [fn(&String) -> bool; 4] = [
+        is_json_media_type,
+        |media_type| matches_content_type(media_type, "application/x-www-form-urlencoded"),
+        |media_type| matches_content_type(media_type, "multipart/*"),
+        |media_type| {
+            let media_type = get_essence(media_type);
+            media_type.ends_with("/xml") || media_type.ends_with("+xml")
+        },
+    ];
+    for is_preferred in priority {
+        let selected = content
+            .iter()
+            .find(|(content_type, _)| is_preferred(content_type));
+        if selected.is_some() {
+            return selected;
+        }
+    }
+
+    return content.first();
+}
+
+/// Media type of JSON Merge Patch (RFC 7396), properties of the body are only the changed ones.
+const MERGE_PATCH_MEDIA_TYPE: &str = "application/merge-patch+json";
+
+/// Media type of JSON Patch (RFC 6902), the body is an array of patch operations.
+const JSON_PATCH_MEDIA_TYPE: &str = "application/json-patch+json";
+
+/// Returns the media type without parameters, lowercased, e.g. `application/json; charset=utf-8` -> `application/json`.
+fn get_essence(media_type: &String) -> String {
+    return media_type.split(';').next().unwrap_or("").trim().to_lowercase();
+}
+
+/// Checks whenever the media type matches the pattern, parameters of the media type are ignored.
+/// Pattern can use `*` as a subtype, e.g. `multipart/*`.
+fn matches_content_type(media_type: &String, pattern: &str) -> bool {
+    let media_type = get_essence(media_type);
+    return match pattern.strip_suffix("/*") {
+        Some(r#type) => media_type.starts_with(&format!("{}/", r#type)),
+        None => media_type == pattern,
+    };
+}
+
+/// Checks whenever the media type is serialized as JSON,
+/// e.g. `application/json`, `application/vnd.api+json` or `application/problem+json`.
+fn is_json_media_type(media_type: &String) -> bool {
+    // parameters like "; charset=utf-8" are not relevant for the serialization
+    let media_type = get_essence(media_type);
+    return media_type.ends_with("/json") || media_type.ends_with("+json");
+}
+
+/// Creates comments from properties of the schema, members of compositions are collected recursively.
+fn create_comment_from_schema(schema: &Schema) -> Vec<Comment> {
+    match schema {
+        Schema::Object(obj) => {
+            let mut comments = create_comment_from_props(&obj.properties, &obj.required);
+            comments.append(&mut create_comment_from_pattern_props(&obj.pattern_properties));
+            comments
+        }
+        Schema::AllOf { allOf: members } => {
+            // members are merged into a single object, so a property is listed once with types of all
+            // its declarations, and it's required when any of the members requires it
+            let required = get_required_properties(schema);
+            let mut comments: Vec<Comment> = Vec::new();
+            for comment in members.iter().flat_map(create_comment_from_schema) {
+                match comments.iter_mut().find(|existing| existing.name == comment.name) {
+                    Some(existing) => existing.possible_types.extend(comment.possible_types),
+                    None => comments.push(comment),
+                }
+            }
+            for comment in comments.iter_mut() {
+                if required.contains(&comment.name) {
+                    comment.required = Some(true);
+                }
+            }
+            comments
+        }
+        Schema::AnyOf { anyOf: members }
+        | Schema::OneOf { oneOf: members, .. }
+        | Schema::Not { not: members } => members
+            .iter()
+            .flat_map(create_comment_from_schema)
+            .collect(),
+    }
+}
+
+/// Collects names of the required properties, `required` of allOf members are merged together.
+fn get_required_properties(schema: &Schema) -> BTreeSet<String> {
+    match schema {
+        Schema::Object(obj) => obj.required.iter().flatten().cloned().collect(),
+        Schema::AllOf { allOf: members } => {
+            members.iter().flat_map(get_required_properties).collect()
+        }
+        _ => BTreeSet::new(),
     }
 }
 
 fn create_comment_from_props(
-    props: &Option<HashMap<String, Schema>>,
+    props: &Option<IndexMap<String, Schema>>,
     required: &Option<Vec<String>>,
 ) -> Vec<Comment> {
     let mut comments = Vec::new();
 
     if let Some(props) = props {
-        for (key, value) in props {
+        // readOnly properties are not sent in requests, writeOnly properties are kept
+        for (key, value) in props.iter().filter(|(_, value)| !value.is_read_only()) {
             let comment = Comment {
                 possible_types: value.get_all_types(),
                 name: key.clone(),
-                default: Some("".to_owned()),
+                default: value.get_default().map(|default| default.to_string()),
                 required: Some(
                     required
                         .clone()
@@ -280,6 +1345,9 @@ fn create_comment_from_props(
                         .contains(&key.clone()),
                 ),
                 description: None,
+                deprecated: value.is_deprecated(),
+                constant: value.get_const().is_some(),
+                location: None,
             };
             comments.push(comment);
         }
@@ -287,12 +1355,131 @@ fn create_comment_from_props(
     return comments;
 }
 
+/// Creates comments for `patternProperties`, noting the pattern of the keys.
+fn create_comment_from_pattern_props(props: &Option<IndexMap<String, Schema>>) -> Vec<Comment> {
+    let props = match props {
+        Some(props) => props,
+        None => return Vec::new(),
+    };
+
+    return props
+        .iter()
+        .map(|(pattern, value)| Comment {
+            possible_types: value.get_all_types(),
+            name: String::from(PATTERN_KEY),
+            default: None,
+            required: Some(false),
+            description: Some(format!("Keys matching {}", pattern)),
+            deprecated: value.is_deprecated(),
+            constant: value.get_const().is_some(),
+            location: None,
+        })
+        .collect();
+}
+
+/// Creates Authorization header, API key cookie & comments of the security scheme.
+fn create_auth(
+    auth: open_api::SecuritySchema,
+    config: &Config,
+) -> (Option<String>, Option<String>, Vec<Comment>) {
+    let mut auth_header = None;
+    let mut cookie = None;
+    let mut security = Vec::new();
+    match auth {
+        open_api::SecuritySchema::BearerToken(bearer) => {
+            auth_header = Some(format!("Authorization: {} {{{{TOKEN}}}}", config.auth_scheme));
+            // the header speaks for itself, only its description is worth a comment
+            if let Some(description) = bearer.description {
+                let comment = Comment {
+                    possible_types: BTreeSet::from([PrimitiveType::String]),
+                    name: String::from("Authorization"),
+                    required: Some(true),
+                    default: None,
+                    description: Some(description),
+                    deprecated: false,
+                    constant: false,
+                    location: None,
+                };
+                if !config.no_auth_comments {
+                    security.push(comment);
+                }
+            }
+        }
+        open_api::SecuritySchema::ApiKey(api_key) => {
+            if let open_api::SecurityTokenLocation::Cookie = api_key.r#in {
+                cookie = Some(format!("{}={{{{{}}}}}", api_key.name, api_key.name));
+            }
+            let comment = Comment {
+                possible_types: BTreeSet::from([PrimitiveType::String]),
+                name: api_key.name,
+                required: Some(true),
+                default: None,
+                description: Some(match api_key.description {
+                    Some(description) => format!(
+                        "Located in {}. {}",
+                        &api_key.r#in.to_string(),
+                        description
+                    ),
+                    None => format!("Located in {}", &api_key.r#in.to_string()),
+                }),
+                deprecated: false,
+                constant: false,
+                location: None,
+            };
+            if !config.no_auth_comments {
+                security.push(comment);
+            }
+        }
+        open_api::SecuritySchema::Unknown(_) => {
+            // not implemented
+        }
+    }
+    return (auth_header, cookie, security);
+}
+
+/// Returns Cookie header with API key cookies of the auth followed by the cookie parameters.
+fn get_cookie_header(auth_cookies: &Vec<String>, cookies: &Vec<String>) -> Option<String> {
+    let all_cookies: Vec<&str> = auth_cookies
+        .iter()
+        .chain(cookies.iter())
+        .map(|cookie| cookie.as_str())
+        .collect();
+    if all_cookies.is_empty() {
+        return None;
+    }
+    return Some(format!("Cookie: {}", all_cookies.join("; ")));
+}
+
+/// Returns security schemes of the requirements following the first one, which is used by the request itself.
+/// Requirements without any scheme (optional auth) & with unknown schemes are skipped.
+fn get_auth_alternatives(
+    auth_options: &Vec<HashMap<String, Vec<String>>>,
+    security_schema: &HashMap<String, open_api::SecuritySchema>,
+) -> Vec<(String, open_api::SecuritySchema)> {
+    return auth_options
+        .iter()
+        .skip(1)
+        .filter_map(|auth| auth.keys().next())
+        .filter_map(|name| match security_schema.get(name) {
+            Some(schema) => Some((name.clone(), schema.clone())),
+            None => {
+                warn(&format!("{} is missing in the security_schema defition", name));
+                None
+            }
+        })
+        .collect();
+}
+
 fn get_auth_schema(
     auth_options: &Vec<HashMap<String, Vec<String>>>,
     security_schema: &HashMap<String, open_api::SecuritySchema>,
 ) -> Option<open_api::SecuritySchema> {
     // TODO: There will need to be some kind of CLI prop,
     // where user will be able to select / create priority map.
+    if auth_options.is_empty() {
+        // `security: []` explicitly disables the authentication
+        return None;
+    }
     for auth in auth_options {
         // First element in the object should always be a name of the security schema.
         let auth_name = auth.keys().next();
@@ -301,13 +1488,345 @@ fn get_auth_schema(
             let schema = match security_schema.get(name) {
                 Some(it) => it,
                 None => {
-                    eprintln!("[warn] {} is missing in the security_schema defition", name);
+                    warn(&format!("{} is missing in the security_schema defition", name));
                     return None;
                 }
             };
             return Some(schema.clone());
         }
     }
-    eprintln!("[warn] Matching security schema was not found");
+    warn(&String::from("Matching security schema was not found"));
     return None;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{from_value, json};
+
+    /// Creates formatted request of the operation with the arguments.
+    fn create_request(
+        path: &str,
+        method: open_api::HttpMethod,
+        operation: Value,
+        args: &[&str],
+    ) -> String {
+        let config = Config::from_test_args(args);
+        let names = Names::new(&String::from(path), &config);
+        let operation: Operation = from_value(operation).unwrap();
+        return HttpData::new(&names, &operation, &method, &None, &config)
+            .unwrap()
+            .get_formatted();
+    }
+
+    #[test]
+    fn json_suffix_media_type_gets_body() {
+        let request = create_request(
+            "/articles",
+            open_api::HttpMethod::post,
+            json!({
+                "requestBody": {
+                    "content": {
+                        "application/vnd.api+json": {
+                            "schema": { "type": "object", "properties": { "title": { "type": "string" } } }
+                        }
+                    }
+                }
+            }),
+            &[],
+        );
+
+        assert!(request.contains("Content-Type: application/vnd.api+json\n"));
+        assert!(request.ends_with("{\n  \"title\": \"\"\n}"));
+    }
+
+    #[test]
+    fn path_params_are_required() {
+        let request = create_request(
+            "/users/{id}",
+            open_api::HttpMethod::get,
+            json!({
+                "parameters": [
+                    { "name": "id", "in": "path", "schema": { "type": "string" } },
+                    { "name": "q", "in": "query", "schema": { "type": "string" } }
+                ]
+            }),
+            &[],
+        );
+
+        assert!(request.contains("# Parameters\n#  - id: String\n"));
+        assert!(request.contains("#  - q?: String\n"));
+    }
+
+    #[test]
+    fn request_names_are_unique_within_file() {
+        let config = Config::from_test_args(&["--request-names"]);
+        let names = Names::new(&String::from("/users"), &config);
+        let operation: Operation = from_value(json!({ "operationId": "users" })).unwrap();
+        let mut used_names = HashSet::new();
+
+        let mut requests = [open_api::HttpMethod::get, open_api::HttpMethod::post]
+            .map(|method| HttpData::new(&names, &operation, &method, &None, &config).unwrap());
+        for request in &mut requests {
+            request.make_name_unique(&mut used_names);
+        }
+
+        assert!(requests[0].get_formatted().starts_with("# @name users\nGET /users"));
+        assert!(requests[1].get_formatted().starts_with("# @name users-post\nPOST /users"));
+    }
+
+    #[test]
+    fn names_of_variants_are_unique_within_file() {
+        let config = Config::from_test_args(&["--request-names", "--expand-variants"]);
+        let names = Names::new(&String::from("/pets"), &config);
+        let get_pet: Operation = from_value(json!({ "operationId": "pets-dog" })).unwrap();
+        let create_pet: Operation = from_value(json!({
+            "operationId": "pets",
+            "requestBody": {
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "oneOf": [
+                                { "title": "dog", "type": "object" },
+                                { "title": "cat", "type": "object" }
+                            ],
+                            "discriminator": { "propertyName": "kind" }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let mut used_names = HashSet::new();
+
+        let mut get_request =
+            HttpData::new(&names, &get_pet, &open_api::HttpMethod::get, &None, &config).unwrap();
+        get_request.make_name_unique(&mut used_names);
+        let mut create_request =
+            HttpData::new(&names, &create_pet, &open_api::HttpMethod::post, &None, &config)
+                .unwrap();
+        create_request.make_name_unique(&mut used_names);
+        let formatted = create_request.get_formatted();
+
+        assert!(get_request.get_formatted().starts_with("# @name pets-dog\nGET /pets"));
+        assert!(formatted.starts_with("# @name pets-dog-2\nPOST /pets"));
+        assert!(formatted.contains("\n###\n\n# @name pets-cat\nPOST /pets"));
+    }
+
+    #[test]
+    fn optional_nullable_query_params_are_commented_out() {
+        let request = create_request(
+            "/users",
+            open_api::HttpMethod::get,
+            json!({
+                "parameters": [
+                    { "name": "cursor", "in": "query", "schema": { "type": "string", "nullable": true } },
+                    {
+                        "name": "limit",
+                        "in": "query",
+                        "required": true,
+                        "schema": { "type": ["integer", "null"] }
+                    }
+                ]
+            }),
+            &[],
+        );
+
+        assert!(request.contains("#  - cursor?: String - Nullable, commented out in the query\n"));
+        assert!(request.ends_with("GET /users?limit=\n#   &cursor=\nhost: {{HTTP_HOST}}"));
+    }
+
+    #[test]
+    fn all_of_members_merge_properties_and_required() {
+        let request = create_request(
+            "/pets",
+            open_api::HttpMethod::post,
+            json!({
+                "requestBody": {
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "allOf": [
+                                    {
+                                        "type": "object",
+                                        "required": ["name"],
+                                        "properties": {
+                                            "name": { "type": "string" },
+                                            "age": { "type": "integer" }
+                                        }
+                                    },
+                                    {
+                                        "type": "object",
+                                        "required": ["age"],
+                                        "properties": {
+                                            "name": { "type": "integer" },
+                                            "tag": { "type": "string" }
+                                        }
+                                    }
+                                ]
+                            }
+                        }
+                    }
+                }
+            }),
+            &[],
+        );
+
+        assert!(request.starts_with(
+            "# Body\n#  - name: String,Integer\n#  - age: Integer\n#  - tag?: String\n"
+        ));
+        assert!(request.ends_with("{\n  \"name\": \"\",\n  \"age\": 0,\n  \"tag\": \"\"\n}"));
+    }
+
+    #[test]
+    fn request_with_some_sections_has_exact_output() {
+        let request = create_request(
+            "/users",
+            open_api::HttpMethod::post,
+            json!({
+                "parameters": [{ "name": "dryRun", "in": "query", "schema": { "type": "boolean" } }],
+                "requestBody": {
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": "object",
+                                "required": ["name"],
+                                "properties": { "name": { "type": "string" } }
+                            }
+                        }
+                    }
+                }
+            }),
+            &[],
+        );
+
+        assert_eq!(
+            request,
+            [
+                "# Query",
+                "#  - dryRun?: String",
+                "#",
+                "# Body",
+                "#  - name: String",
+                "#",
+                "POST /users?dryRun=",
+                "host: {{HTTP_HOST}}",
+                "Content-Type: application/json",
+                "",
+                "{",
+                "  \"name\": \"\"",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn trims_blank_lines_and_trailing_whitespace() {
+        let value = String::from("\n# Body  \n\n\n\nGET /users\t\n\n");
+
+        assert_eq!(trim_blank_lines(&value), "# Body\n\nGET /users");
+    }
+
+    #[test]
+    fn template_keeps_its_blank_lines() {
+        let config = Config::from_test_args(&[]);
+        let names = Names::new(&String::from("/users"), &config);
+        let operation: Operation = from_value(json!({})).unwrap();
+        let mut request =
+            HttpData::new(&names, &operation, &open_api::HttpMethod::get, &None, &config).unwrap();
+        request.template = Some(String::from(
+            "{{comments}}\n{{method}} {{path}}\n{{host}}\n\n\n# end of {{path}}\n{{body}}\n",
+        ));
+
+        assert_eq!(
+            request.get_formatted(),
+            "GET /users\nhost: {{HTTP_HOST}}\n\n\n# end of /users"
+        );
+    }
+
+    #[test]
+    fn write_only_properties_are_only_in_requests() {
+        let user = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "readOnly": true },
+                "name": { "type": "string" },
+                "password": { "type": "string", "writeOnly": true }
+            }
+        });
+        let request = create_request(
+            "/users",
+            open_api::HttpMethod::post,
+            json!({
+                "requestBody": { "content": { "application/json": { "schema": user } } },
+                "responses": {}
+            }),
+            &[],
+        );
+        let request_from_response = create_request(
+            "/users",
+            open_api::HttpMethod::post,
+            json!({
+                "requestBody": { "content": { "application/json": { "schema": user } } },
+                "responses": {
+                    "201": {
+                        "description": "Created",
+                        "content": {
+                            "application/json": {
+                                "schema": user,
+                                "example": { "id": 1, "name": "Jane", "password": "secret" }
+                            }
+                        }
+                    }
+                }
+            }),
+            &["--example-from-response"],
+        );
+
+        assert!(request.contains("\"password\": \"\""));
+        assert!(!request.contains("\"id\""));
+        assert!(request_from_response.contains("\"name\": \"Jane\""));
+        assert!(!request_from_response.contains("\"password\""));
+        assert!(!request_from_response.contains("\"id\""));
+    }
+
+    #[test]
+    fn optional_body_without_properties_gets_heading() {
+        let request = create_request(
+            "/events",
+            open_api::HttpMethod::post,
+            json!({
+                "requestBody": {
+                    "required": false,
+                    "content": { "application/json": { "schema": { "type": "object" } } }
+                }
+            }),
+            &[],
+        );
+
+        assert!(request.starts_with("# Body (optional)\n#\nPOST /events\n"));
+    }
+
+    #[test]
+    fn external_example_is_body_only_when_fetched() {
+        let operation: Operation = from_value(json!({
+            "requestBody": {
+                "content": {
+                    "application/json": {
+                        "examples": {
+                            "pet": { "externalValue": "https://example.com/pet.json" }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        assert!(!HttpData::has_body(&operation, &Config::from_test_args(&[])));
+        assert!(HttpData::has_body(
+            &operation,
+            &Config::from_test_args(&["--fetch-examples"])
+        ));
+    }
+}
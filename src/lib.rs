@@ -2,4 +2,12 @@ pub mod utils;
 pub mod open_api;
 pub mod app;
 pub mod http_data;
+pub mod names;
 pub mod comment;
+pub mod skeleton;
+pub mod reference;
+pub mod output;
+pub mod error;
+pub mod watch;
+pub mod snippet;
+pub mod logging;
@@ -0,0 +1,89 @@
+use serde_json::{json, Map, Value};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Format of the messages printed during the run.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines, e.g. `[warn] /customers has no operations, skipping`.
+    Text,
+    /// JSON lines with level, message & context of the record, e.g. `{"level":"warn","message":"...","path":"/customers"}`.
+    Json,
+}
+
+impl LogFormat {
+    /// Returns the format by its name, i.e. `text` or `json`.
+    pub fn from_name(name: &String) -> Option<Self> {
+        match name.as_str() {
+            "text" => Some(LogFormat::Text),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Messages are printed as JSON lines, see `set_log_format`.
+static JSON_FORMAT: AtomicBool = AtomicBool::new(false);
+
+/// Number of warnings reported during the run.
+static WARNINGS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets format of all the messages printed from then on.
+pub fn set_log_format(format: LogFormat) {
+    JSON_FORMAT.store(format == LogFormat::Json, Ordering::SeqCst);
+}
+
+/// Reports the warning to stderr, warnings are counted, so the run can fail on them with `--fail-on-warnings`.
+pub fn warn(message: &String) {
+    warn_at(message, &[]);
+}
+
+/// Reports the warning together with its context, e.g. path of the endpoint, see `warn`.
+/// Context is part of JSON records only, text messages are expected to mention it already.
+pub fn warn_at(message: &String, context: &[(&str, &String)]) {
+    WARNINGS_COUNT.fetch_add(1, Ordering::SeqCst);
+    print_record("warn", message, context);
+}
+
+/// Reports the error to stderr, the run continues, e.g. with other files.
+pub fn error_at(message: &String, context: &[(&str, &String)]) {
+    print_record("error", message, context);
+}
+
+/// Reports the failure which ends the run, the text message is printed without a level.
+pub fn failure(message: &String) {
+    if JSON_FORMAT.load(Ordering::SeqCst) {
+        print_record("error", message, &[]);
+    } else {
+        eprintln!("{}", message);
+    }
+}
+
+/// Reports progress of a long running command, e.g. `--watch`, to stdout.
+/// JSON records are printed to stderr together with the other records, so stdout stays clean.
+pub fn progress(source: &str, message: &String) {
+    if JSON_FORMAT.load(Ordering::SeqCst) {
+        print_record("info", message, &[("source", &source.to_owned())]);
+    } else {
+        println!("[{}] {}", source, message);
+    }
+}
+
+/// Returns number of warnings reported so far.
+pub fn get_warnings_count() -> usize {
+    return WARNINGS_COUNT.load(Ordering::SeqCst);
+}
+
+fn print_record(level: &str, message: &String, context: &[(&str, &String)]) {
+    if !JSON_FORMAT.load(Ordering::SeqCst) {
+        eprintln!("[{}] {}", level, message);
+        return;
+    }
+
+    let mut record = Map::new();
+    record.insert(String::from("level"), json!(level));
+    record.insert(String::from("message"), json!(message));
+    for (key, value) in context {
+        record.insert(key.to_string(), json!(value));
+    }
+    eprintln!("{}", Value::Object(record));
+}
@@ -1,11 +1,15 @@
-use open_api_to_http::app::Application;
+use open_api_to_http::{app::Application, error::AppError, logging::failure};
 
 fn main() {
-    let app = Application::prepare().unwrap_or_else(|code| {
-        std::process::exit(code);
-    });
+    let app = Application::prepare().unwrap_or_else(|err| exit(err));
 
-    app.run().unwrap_or_else(|code| {
-        std::process::exit(code);
-    });
+    app.run().unwrap_or_else(|err| exit(err));
+}
+
+/// Reports the error to stderr and exits with its exit code.
+fn exit(err: AppError) -> ! {
+    if !matches!(err, AppError::Help) {
+        failure(&err.to_string());
+    }
+    std::process::exit(err.exit_code());
 }
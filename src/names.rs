@@ -0,0 +1,442 @@
+use crate::{
+    app::Config,
+    open_api::{self, Operation},
+};
+use indexmap::IndexMap;
+
+/// Checks whenever the path segment is a parameter, e.g. `{id}` or `{id}.json`.
+fn is_path_param(segment: &str) -> bool {
+    return segment.starts_with("{") && segment.contains("}");
+}
+
+/// Source of the names of single operations, see `--request-naming`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RequestNaming {
+    /// operationId, falls back to method & path.
+    OperationId,
+    /// Slugified summary, falls back to operationId, then to method & path.
+    Summary,
+    /// Method & path, e.g. `get-customers-id`.
+    Path,
+}
+
+impl RequestNaming {
+    /// All the sources, in order they are listed in the help.
+    pub const ALL: [RequestNaming; 3] = [
+        RequestNaming::OperationId,
+        RequestNaming::Summary,
+        RequestNaming::Path,
+    ];
+
+    /// Returns the source by its name, e.g. `operationId`.
+    pub fn from_name(name: &String) -> Option<Self> {
+        return RequestNaming::ALL
+            .into_iter()
+            .find(|naming| naming.get_name() == name);
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            RequestNaming::OperationId => "operationId",
+            RequestNaming::Summary => "summary",
+            RequestNaming::Path => "path",
+        }
+    }
+}
+
+/// Names of the files & folders for requests of an endpoint.
+#[derive(Clone, Debug)]
+pub struct Names {
+    /// Folders which have to exist for the file, each of them including its parents,
+    /// e.g. `customers` & `customers/orders` for `/customers/orders/items`.
+    pub folders: Vec<String>,
+    /// Path of the file (without extension) relative to the output folder, e.g. `/customers/orders/items`.
+    pub file_path: String,
+    /// Last segment of the file path, e.g. `items`.
+    pub file_name: String,
+    /// Path of the endpoint with repeated slashes collapsed, used in the requests.
+    pub http_path: String,
+}
+
+/// Case of the file & folder names, see `--path-case`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PathCase {
+    /// Segments are kept as they are in the path.
+    Original,
+    /// e.g. `customer-orders`
+    Kebab,
+    /// e.g. `customer_orders`
+    Snake,
+    /// e.g. `customerOrders`
+    Camel,
+}
+
+impl PathCase {
+    /// All the cases, in order they are listed in the help.
+    pub const ALL: [PathCase; 4] = [
+        PathCase::Kebab,
+        PathCase::Snake,
+        PathCase::Camel,
+        PathCase::Original,
+    ];
+
+    /// Returns the case by its name, e.g. `kebab`.
+    pub fn from_name(name: &String) -> Option<Self> {
+        return PathCase::ALL.into_iter().find(|case| case.get_name() == name);
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            PathCase::Original => "original",
+            PathCase::Kebab => "kebab",
+            PathCase::Snake => "snake",
+            PathCase::Camel => "camel",
+        }
+    }
+
+    /// Converts the path segment to the case, e.g. `customerOrders` -> `customer-orders`.
+    /// Words are separated by non-alphanumeric characters & by lowercase to uppercase transitions.
+    pub fn apply(&self, segment: &String) -> String {
+        let words = split_words(segment);
+        match self {
+            PathCase::Original => segment.clone(),
+            PathCase::Kebab => words.join("-"),
+            PathCase::Snake => words.join("_"),
+            PathCase::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) if i > 0 => first.to_uppercase().chain(chars).collect(),
+                        _ => word.clone(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Splits the segment into lowercase words, e.g. `customerOrders_v2` -> `customer`, `orders`, `v2`.
+fn split_words(segment: &String) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lowercase = false;
+    for c in segment.chars() {
+        if !c.is_alphanumeric() {
+            previous_lowercase = false;
+            if word.len() > 0 {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && previous_lowercase {
+            words.push(std::mem::take(&mut word));
+        }
+        previous_lowercase = c.is_lowercase() || c.is_numeric();
+        word.extend(c.to_lowercase());
+    }
+    if word.len() > 0 {
+        words.push(word);
+    }
+    return words;
+}
+
+/// Collapses repeated slashes in the path, e.g. `/users//{id}` -> `/users/{id}`.
+fn normalize_path(path: &String) -> String {
+    let mut normalized = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c == '/' && normalized.ends_with('/') {
+            continue;
+        }
+        normalized.push(c);
+    }
+    return normalized;
+}
+
+/// Replaces accented latin characters with their ASCII equivalents, other non-ASCII characters are removed.
+fn to_ascii(value: &String) -> String {
+    let mut output = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii() {
+            output.push(c);
+            continue;
+        }
+
+        let replacement = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+            'ç' | 'ć' | 'č' => "c",
+            'Ç' | 'Ć' | 'Č' => "C",
+            'ď' | 'đ' => "d",
+            'Ď' | 'Đ' => "D",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+            'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' => "i",
+            'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'Į' => "I",
+            'ľ' | 'ĺ' | 'ł' => "l",
+            'Ľ' | 'Ĺ' | 'Ł' => "L",
+            'ñ' | 'ń' | 'ň' => "n",
+            'Ñ' | 'Ń' | 'Ň' => "N",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+            'ŕ' | 'ř' => "r",
+            'Ŕ' | 'Ř' => "R",
+            'ś' | 'š' | 'ş' => "s",
+            'Ś' | 'Š' | 'Ş' => "S",
+            'ť' | 'ţ' => "t",
+            'Ť' | 'Ţ' => "T",
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+            'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' | 'Ų' => "U",
+            'ý' | 'ÿ' => "y",
+            'Ý' | 'Ÿ' => "Y",
+            'ź' | 'ż' | 'ž' => "z",
+            'Ź' | 'Ż' | 'Ž' => "Z",
+            'ß' => "ss",
+            'æ' => "ae",
+            'Æ' => "AE",
+            _ => "",
+        };
+        output.push_str(replacement);
+    }
+    return output;
+}
+
+impl Names {
+    /// Returns name of a single operation of the path, used for its file (without extension) & `@name`,
+    /// by the source of `--request-naming`, e.g. operationId, with fallback to method & path, e.g. `get-customers-id`.
+    pub fn get_operation_file_name(
+        &self,
+        method: &open_api::HttpMethod,
+        endpoint_info: &Operation,
+        config: &Config,
+    ) -> String {
+        let summary = endpoint_info
+            .summary
+            .as_ref()
+            .map(|summary| summary.to_lowercase())
+            .filter(|summary| summary.chars().any(|c| c.is_alphanumeric()));
+        let name = match config.request_naming {
+            RequestNaming::OperationId => endpoint_info.operation_id.clone(),
+            RequestNaming::Summary => summary.or(endpoint_info.operation_id.clone()),
+            RequestNaming::Path => None,
+        };
+        let name = name.unwrap_or_else(|| format!("{:?} {}", method, self.http_path));
+        let name = if config.ascii_only { to_ascii(&name) } else { name };
+
+        // characters which are not safe in file names are replaced, e.g. `/users/{id}` -> `users-id`
+        return name
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .filter(|part| part.len() > 0)
+            .collect::<Vec<&str>>()
+            .join("-");
+    }
+
+    /// Creates names for the path of the endpoint, names are derived as follows:
+    /// - repeated slashes are collapsed, e.g. `/users//{id}` -> `/users/{id}`, the request uses the collapsed path
+    /// - empty segments (leading & trailing slashes) and parameters, e.g. `{id}`, are left out
+    /// - segments are converted with `--ascii-only` & `--path-case`, segments which end up empty are left out
+    /// - path without any segment, e.g. `/` or `/{id}`, is named `index`
+    /// - segments deeper than `--group-depth` are joined into the file name with `_`
+    /// - the last segment is the file name, the others are the folders
+    ///
+    /// e.g. `/customers/{id}/orders/` -> folders `customers`, file path `/customers/orders`, file name `orders`.
+    pub fn new(value: &String, config: &Config) -> Self {
+        let http_path = normalize_path(value);
+        let mut splits: Vec<String> = http_path
+            .split('/')
+            .filter(|split| split.len() > 0 && !is_path_param(split))
+            .map(String::from)
+            .collect();
+
+        // request keeps the original path, only file & folder names are converted
+        if config.ascii_only {
+            splits = splits.iter().map(to_ascii).collect();
+            splits.retain(|split| split.len() > 0);
+        }
+
+        if config.path_case != PathCase::Original {
+            splits = splits.iter().map(|split| config.path_case.apply(split)).collect();
+            splits.retain(|split| split.len() > 0);
+        }
+
+        // root path (or path made only of query params) has no name of its own
+        if splits.len() == 0 {
+            splits.push(String::from("index"));
+        }
+
+        // segments deeper than the group depth are flattened into the file name, e.g. depth 1: /a/b/c -> a/b_c
+        if let Some(depth) = config.group_depth {
+            if splits.len() > depth + 1 {
+                let flattened = splits.split_off(depth).join("_");
+                splits.push(flattened);
+            }
+        }
+
+        let file_path = format!("/{}", splits.join("/"));
+        // there is always at least one segment, see `index` above
+        let file_name = splits.pop().unwrap_or_default();
+        let folders = (1..=splits.len())
+            .map(|depth| splits[..depth].join("/"))
+            .collect();
+
+        return Names {
+            file_name,
+            folders,
+            file_path,
+            http_path,
+        };
+    }
+}
+
+/// Finds paths which end up in the same file only because of the normalization, e.g. `/Users` & `/users`
+/// with `--path-case kebab`. Paths differing only by their parameters, e.g. `/users` & `/users/{id}`,
+/// share the file by design and are not reported.
+///
+/// Returns file path & description of every collision, e.g. `/Users and /users are written to /users.http`.
+pub fn find_file_path_collisions<'a>(
+    paths: impl Iterator<Item = &'a String>,
+    config: &Config,
+) -> Vec<(String, String)> {
+    // paths of every file by their segments without parameters, first path of the segments represents them
+    let mut files = IndexMap::<String, IndexMap<String, &String>>::new();
+    for path in paths {
+        let segments = normalize_path(path)
+            .split('/')
+            .filter(|split| split.len() > 0 && !is_path_param(split))
+            .collect::<Vec<&str>>()
+            .join("/");
+        files
+            .entry(Names::new(path, config).file_path)
+            .or_default()
+            .entry(segments)
+            .or_insert(path);
+    }
+
+    return files
+        .into_iter()
+        .filter(|(_, sources)| sources.len() > 1)
+        .map(|(file_path, sources)| {
+            let paths = sources
+                .values()
+                .map(|path| path.as_str())
+                .collect::<Vec<&str>>()
+                .join(" and ");
+            let message = format!("{} are written to {}.http", paths, file_path);
+            (file_path, message)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapses_duplicate_slashes() {
+        let config = Config::from_test_args(&[]);
+        let names = Names::new(&String::from("/users//{id}"), &config);
+
+        assert_eq!(names.http_path, "/users/{id}");
+        assert_eq!(names.file_path, "/users");
+        assert_eq!(names.file_name, "users");
+        assert!(names.folders.is_empty());
+
+        let names = Names::new(&String::from("//"), &config);
+        assert_eq!(names.http_path, "/");
+        assert_eq!(names.file_path, "/index");
+    }
+
+    #[test]
+    fn ascii_only_transliterates_file_names() {
+        let config = Config::from_test_args(&["--ascii-only"]);
+        let names = Names::new(&String::from("/café/crème-brûlée/日本"), &config);
+
+        assert_eq!(names.http_path, "/café/crème-brûlée/日本");
+        assert_eq!(names.file_path, "/cafe/creme-brulee");
+        assert_eq!(names.folders, ["cafe"]);
+    }
+
+    #[test]
+    fn path_case_converts_segments() {
+        let segment = String::from("customerOrders_v2-Items");
+
+        assert_eq!(PathCase::Kebab.apply(&segment), "customer-orders-v2-items");
+        assert_eq!(PathCase::Snake.apply(&segment), "customer_orders_v2_items");
+        assert_eq!(PathCase::Camel.apply(&segment), "customerOrdersV2Items");
+        assert_eq!(PathCase::Original.apply(&segment), "customerOrders_v2-Items");
+    }
+
+    #[test]
+    fn path_case_keeps_request_path() {
+        let config = Config::from_test_args(&["--path-case", "snake"]);
+        let names = Names::new(&String::from("/userAccounts/{id}/paymentMethods"), &config);
+
+        assert_eq!(names.http_path, "/userAccounts/{id}/paymentMethods");
+        assert_eq!(names.file_path, "/user_accounts/payment_methods");
+    }
+
+    #[test]
+    fn root_path_is_index() {
+        let names = Names::new(&String::from("/"), &Config::from_test_args(&[]));
+
+        assert_eq!(names.http_path, "/");
+        assert_eq!(names.file_path, "/index");
+        assert_eq!(names.file_name, "index");
+        assert!(names.folders.is_empty());
+    }
+
+    #[test]
+    fn path_of_only_params_is_index() {
+        let names = Names::new(&String::from("/{tenant}/{id}"), &Config::from_test_args(&[]));
+
+        assert_eq!(names.http_path, "/{tenant}/{id}");
+        assert_eq!(names.file_path, "/index");
+        assert!(names.folders.is_empty());
+    }
+
+    #[test]
+    fn nested_path_accumulates_folders() {
+        let config = Config::from_test_args(&[]);
+        let names = Names::new(&String::from("/customers/{id}/orders/{orderId}/items"), &config);
+
+        assert_eq!(names.folders, ["customers", "customers/orders"]);
+        assert_eq!(names.file_path, "/customers/orders/items");
+        assert_eq!(names.file_name, "items");
+    }
+
+    #[test]
+    fn trailing_and_duplicate_slashes_are_ignored() {
+        let config = Config::from_test_args(&[]);
+        let names = Names::new(&String::from("//customers///orders//"), &config);
+
+        assert_eq!(names.http_path, "/customers/orders/");
+        assert_eq!(names.folders, ["customers"]);
+        assert_eq!(names.file_path, "/customers/orders");
+        assert_eq!(names.file_name, "orders");
+    }
+
+    #[test]
+    fn finds_file_path_collisions() {
+        let config = Config::from_test_args(&["--path-case", "kebab"]);
+        let paths = [
+            String::from("/UserAccounts"),
+            String::from("/user-accounts/{id}"),
+            String::from("/user_accounts"),
+            String::from("/orders"),
+            String::from("/orders/{id}"),
+        ];
+
+        assert_eq!(
+            find_file_path_collisions(paths.iter(), &config),
+            [(
+                String::from("/user-accounts"),
+                String::from(
+                    "/UserAccounts and /user-accounts/{id} and /user_accounts are written to /user-accounts.http"
+                ),
+            )]
+        );
+        assert!(find_file_path_collisions(paths.iter(), &Config::from_test_args(&[])).is_empty());
+    }
+}
@@ -1,8 +1,13 @@
+use crate::{
+    error::AppError,
+    reference::{load_document, resolve_refs},
+};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_str, Value};
+use serde_json::{from_value, json, to_value, Value};
 use std::{
-    collections::{HashMap, HashSet},
-    fs,
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
 };
 
 /// Describes a single API operation on a path.
@@ -10,98 +15,426 @@ use std::{
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Operation {
-    pub responses: Option<HashMap<i32, Response>>,
+    /// Unique string used to identify the operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// Short summary of what the operation does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Responses by their status code, e.g. `200`, `2XX` or `default`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responses: Option<IndexMap<StatusCode, Response>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub parameters: Option<Vec<Parameters>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub request_body: Option<RequestBody>,
     /// Lists the required security schemes to execute this operation.
     /// The name used for each property MUST correspond to a security scheme
     /// declared in the Security Schemes under the Components Object.
     /// ref: https://spec.openapis.org/oas/v3.1.0#security-requirement-object
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub security: Option<Vec<HashMap<String, Vec<String>>>>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Parameters {
-    pub r#in: String,
+    pub r#in: ParameterLocation,
+    /// Missing when the parameter is described by `content` instead.
+    #[serde(default)]
     pub schema: Value,
+    /// Media type of complex parameters, e.g. JSON serialized filter in the query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<IndexMap<String, MediaType>>,
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
-    pub default: Option<String>,
+    /// Default of Swagger 2.0, OpenAPI 3 declares it in the schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<IndexMap<String, Example>>,
+}
+
+impl Parameters {
+    /// Returns constant value of the parameter schema.
+    pub fn get_const(&self) -> Option<String> {
+        return self.schema.get("const").map(value_to_string);
+    }
+
+    /// Returns content type & media type of the parameter described by `content`.
+    pub fn get_content(&self) -> Option<(&String, &MediaType)> {
+        return self.content.as_ref().and_then(|content| content.first());
+    }
+
+    /// Checks whenever the parameter accepts null, i.e. `nullable` of OpenAPI 3.0,
+    /// or `null` in the types of OpenAPI 3.1.
+    pub fn is_nullable(&self) -> bool {
+        if self.schema.get("nullable").and_then(|nullable| nullable.as_bool()) == Some(true) {
+            return true;
+        }
+        return match self.schema.get("type") {
+            Some(Value::Array(types)) => types.iter().any(|r#type| r#type == "null"),
+            _ => false,
+        };
+    }
+
+    /// Returns allowed values of the parameter schema, empty when they are not restricted.
+    pub fn get_enum(&self) -> Vec<String> {
+        return self
+            .schema
+            .get("enum")
+            .and_then(|values| values.as_array())
+            .map_or(Vec::new(), |values| values.iter().map(value_to_string).collect());
+    }
+
+    /// Returns default of the parameter schema, with fallback to default of the parameter itself.
+    pub fn get_default(&self) -> Option<String> {
+        return self
+            .schema
+            .get("default")
+            .or(self.default.as_ref())
+            .map(value_to_string);
+    }
+
+    /// Returns example of the parameter, first of the examples is preferred over the example.
+    /// Examples of the parameter are preferred over the examples of its schema.
+    pub fn get_example(&self) -> Option<String> {
+        return self
+            .examples
+            .as_ref()
+            .and_then(|examples| examples.values().find_map(|example| example.value.as_ref()))
+            .or(self.example.as_ref())
+            .or(self
+                .schema
+                .get("examples")
+                .and_then(|examples| examples.as_array())
+                .and_then(|examples| examples.first()))
+            .or(self.schema.get("example"))
+            .map(value_to_string);
+    }
+}
+
+/// Sample value of a parameter or a media type.
+/// ref: https://spec.openapis.org/oas/v3.1.0#example-object
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Example {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    /// URL of the example, e.g. `https://example.com/customer.json`, or path relative to the document.
+    #[serde(rename = "externalValue", skip_serializing_if = "Option::is_none")]
+    pub external_value: Option<String>,
+}
+
+/// Creates schema of string property which is always equal to the value.
+fn const_schema(value: &String) -> Schema {
+    return from_value(json!({ "type": "string", "const": value })).unwrap();
+}
+
+/// Converts JSON value into plain string, strings are not quoted.
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Removes readOnly properties from `required` of the JSON schema & of its nested schemas.
+/// Properties of allOf members are merged, so readOnly property of one member is removed from all of them.
+fn strip_read_only_required(schema: &mut Value) {
+    let read_only = get_read_only_properties(schema);
+    remove_required(schema, &read_only);
+}
+
+/// Collects names of the readOnly properties, properties of allOf members included.
+fn get_read_only_properties(schema: &Value) -> BTreeSet<String> {
+    if let Some(Value::Array(members)) = schema.get("allOf") {
+        return members.iter().flat_map(get_read_only_properties).collect();
+    }
+    let is_read_only = |prop: &Value| prop.get("readOnly").and_then(Value::as_bool) == Some(true);
+    return schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .iter()
+        .flat_map(|props| props.iter())
+        .filter(|(_, prop)| is_read_only(prop))
+        .map(|(name, _)| name.clone())
+        .collect();
+}
+
+fn remove_required(schema: &mut Value, names: &BTreeSet<String>) {
+    if let Some(Value::Array(members)) = schema.get_mut("allOf") {
+        for member in members {
+            remove_required(member, names);
+        }
+        return;
+    }
+    if let Some(Value::Array(required)) = schema.get_mut("required") {
+        required.retain(|name| !name.as_str().is_some_and(|name| names.contains(name)));
+    }
+    if let Some(Value::Object(props)) = schema.get_mut("properties") {
+        props.values_mut().for_each(strip_read_only_required);
+    }
+    for key in ["anyOf", "oneOf", "not", "items"] {
+        match schema.get_mut(key) {
+            Some(Value::Array(members)) => members.iter_mut().for_each(strip_read_only_required),
+            Some(member @ Value::Object(_)) => strip_read_only_required(member),
+            _ => {}
+        }
+    }
+}
+
+/// The location of the parameter.
+/// ref: https://spec.openapis.org/oas/v3.1.0#parameter-locations
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterLocation {
+    Query,
+    Header,
+    Path,
+    Cookie,
 }
 
 /// Describes a single request body.
 /// ref: https://spec.openapis.org/oas/v3.1.0#request-body-object
 #[derive(Serialize, Deserialize)]
 pub struct RequestBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    pub content: HashMap<String, MediaType>,
+    pub content: IndexMap<String, MediaType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
 }
 
 /// Each Media Type Object provides schema for the media type identified by its key.
 /// ref: https://spec.openapis.org/oas/v3.1.0#mediaTypeObject
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct MediaType {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub schema: Option<Schema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<IndexMap<String, Example>>,
+}
+
+impl MediaType {
+    /// Returns example of the media type, first of the examples is preferred over the example.
+    pub fn get_example(&self) -> Option<&Value> {
+        return self
+            .examples
+            .as_ref()
+            .and_then(|examples| examples.values().find_map(|example| example.value.as_ref()))
+            .or(self.example.as_ref());
+    }
+
+    /// Returns URL of the first external example, see `Example::external_value`.
+    pub fn get_external_value(&self) -> Option<&String> {
+        return self.examples.as_ref().and_then(|examples| {
+            examples
+                .values()
+                .find_map(|example| example.external_value.as_ref())
+        });
+    }
 }
 
 /// The Schema Object allows the definition of input and output data types. These types can be objects, but also primitives and arrays.
 /// This object is a superset of the JSON Schema Specification Draft 2020-12.
 /// ref: https://spec.openapis.org/oas/v3.1.0#schema-object
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum Schema {
-    Object(Object),
-    AllOf { allOf: Vec<Object> },
-    AnyOf { anyOf: Vec<Object> },
-    OneOf { oneOf: Vec<Object> },
-    Not { not: Vec<Object> },
+    Object(Box<Object>),
+    AllOf { allOf: Vec<Schema> },
+    AnyOf { anyOf: Vec<Schema> },
+    OneOf {
+        oneOf: Vec<Schema>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        discriminator: Option<Discriminator>,
+    },
+    Not { not: Vec<Schema> },
+}
+
+/// Tells members of oneOf apart by value of their property.
+/// ref: https://spec.openapis.org/oas/v3.1.0#discriminator-object
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Discriminator {
+    pub property_name: String,
+    /// Values of the property with references of their schemas, e.g. `dog: '#/components/schemas/Dog'`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<IndexMap<String, String>>,
 }
 
 impl Schema {
-    pub fn get_all_types(&self) -> HashSet<PrimitiveType> {
-        let mut known_types = HashSet::new();
+    /// Collects types of the schema, members of compositions are collected recursively.
+    pub fn get_all_types(&self) -> BTreeSet<PrimitiveType> {
+        let mut known_types = BTreeSet::new();
 
         // TODO: Handle those properly
         match self {
             Schema::Object(obj) => {
                 known_types.insert(obj.r#type.clone());
             }
-            Schema::AllOf { allOf } => {
-                allOf.iter().for_each(|obj| {
-                    known_types.insert(obj.r#type.clone());
-                });
-            }
-            Schema::AnyOf { anyOf } => {
-                anyOf.iter().for_each(|obj| {
-                    known_types.insert(obj.r#type.clone());
-                });
-            }
-            Schema::OneOf { oneOf } => {
-                oneOf.iter().for_each(|obj| {
-                    known_types.insert(obj.r#type.clone());
-                });
-            }
-            Schema::Not { not } => {
-                not.iter().for_each(|obj| {
-                    known_types.insert(obj.r#type.clone());
+            Schema::AllOf { allOf: members }
+            | Schema::AnyOf { anyOf: members }
+            | Schema::OneOf { oneOf: members, .. }
+            | Schema::Not { not: members } => {
+                members.iter().for_each(|member| {
+                    known_types.extend(member.get_all_types());
                 });
             }
         }
 
         return known_types;
     }
+
+    pub fn get_const(&self) -> Option<&Value> {
+        match self {
+            Schema::Object(obj) => obj.r#const.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns example of the schema, first of the examples is preferred over the example.
+    pub fn get_example(&self) -> Option<&Value> {
+        match self {
+            Schema::Object(obj) => obj
+                .examples
+                .as_ref()
+                .and_then(|examples| examples.first())
+                .or(obj.example.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn get_default(&self) -> Option<&Value> {
+        match self {
+            Schema::Object(obj) => obj.default.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_title(&self) -> Option<&String> {
+        match self {
+            Schema::Object(obj) => obj.title.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns const of the property as a plain string, properties of allOf members included.
+    pub fn get_property_const(&self, name: &String) -> Option<String> {
+        match self {
+            Schema::Object(obj) => obj
+                .properties
+                .as_ref()
+                .and_then(|props| props.get(name))
+                .and_then(|prop| prop.get_const())
+                .map(value_to_string),
+            Schema::AllOf { allOf: members } => members
+                .iter()
+                .find_map(|member| member.get_property_const(name)),
+            _ => None,
+        }
+    }
+
+    /// Returns the schema with the property fixed to the string value, e.g. discriminator of oneOf member.
+    /// The property is replaced in the first object declaring it (allOf members included), otherwise it's added.
+    pub fn with_const_property(&self, name: &String, value: &String) -> Schema {
+        let mut schema = self.clone();
+        if !schema.set_const_property(name, value) {
+            if let Schema::Object(obj) = &mut schema {
+                obj.properties
+                    .get_or_insert_with(IndexMap::new)
+                    .insert(name.clone(), const_schema(value));
+            }
+        }
+        return schema;
+    }
+
+    fn set_const_property(&mut self, name: &String, value: &String) -> bool {
+        match self {
+            Schema::Object(obj) => match obj.properties.as_mut().and_then(|props| props.get_mut(name)) {
+                Some(prop) => {
+                    *prop = const_schema(value);
+                    true
+                }
+                None => false,
+            },
+            Schema::AllOf { allOf: members } => members
+                .iter_mut()
+                .any(|member| member.set_const_property(name, value)),
+            _ => false,
+        }
+    }
+
+    /// Checks whenever the property is only sent by the server, so it doesn't belong to requests.
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Schema::Object(obj) => obj.read_only.unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        match self {
+            Schema::Object(obj) => obj.deprecated.unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Removes readOnly properties from `required` of the schema, see `--strip-readonly-required`.
+    pub fn strip_read_only_required(&mut self) {
+        if let Ok(mut value) = to_value(&*self) {
+            strip_read_only_required(&mut value);
+            if let Ok(schema) = from_value(value) {
+                *self = schema;
+            }
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Object {
-    pub properties: Option<HashMap<String, Schema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<IndexMap<String, Schema>>,
+    /// Values of keys matching the pattern, i.e. map with dynamic keys.
+    #[serde(rename = "patternProperties", skip_serializing_if = "Option::is_none")]
+    pub pattern_properties: Option<IndexMap<String, Schema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<Vec<String>>,
     pub r#type: PrimitiveType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+    /// The value is always equal to this constant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#const: Option<Value>,
+    /// Deprecated since OpenAPI 3.1 in favor of `examples`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<Value>>,
+    /// Value used by the server when the property is not sent, of any type, e.g. `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    /// The property is only returned in responses, e.g. generated `id`.
+    #[serde(rename = "readOnly", skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
 }
 
 /// ref: https://datatracker.ietf.org/doc/html/draft-bhutton-json-schema-validation-00#section-6.1.1
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum PrimitiveType {
     String,
@@ -120,9 +453,69 @@ impl ToString for PrimitiveType {
 
 #[derive(Serialize, Deserialize)]
 pub struct Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<HashMap<String, Value>>,
 }
 
+/// Key of the response, i.e. a status code, a range of status codes, or `default`.
+/// Codes go first, then the ranges, and `default` is the last one, when they are sorted.
+/// ref: https://spec.openapis.org/oas/v3.1.0#responses-object
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(try_from = "String", into = "String")]
+pub enum StatusCode {
+    /// e.g. `200`
+    Code(u16),
+    /// First digit of the range, e.g. `2` of `2XX`.
+    Range(u16),
+    /// Response of all the status codes which are not declared.
+    Default,
+}
+
+impl StatusCode {
+    /// Checks whenever the response is successful, i.e. its code or range is 2xx.
+    pub fn is_success(&self) -> bool {
+        match self {
+            StatusCode::Code(code) => (200..300).contains(code),
+            StatusCode::Range(range) => *range == 2,
+            StatusCode::Default => false,
+        }
+    }
+}
+
+impl TryFrom<String> for StatusCode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value == "default" {
+            return Ok(StatusCode::Default);
+        }
+
+        let status_code = match value.strip_suffix("XX").or(value.strip_suffix("xx")) {
+            Some(range) => range
+                .parse::<u16>()
+                .ok()
+                .filter(|range| (1..=5).contains(range))
+                .map(StatusCode::Range),
+            None => value
+                .parse::<u16>()
+                .ok()
+                .filter(|code| (100..=599).contains(code))
+                .map(StatusCode::Code),
+        };
+        return status_code.ok_or(format!("invalid response status code {}", value));
+    }
+}
+
+impl From<StatusCode> for String {
+    fn from(status_code: StatusCode) -> Self {
+        match status_code {
+            StatusCode::Code(code) => code.to_string(),
+            StatusCode::Range(range) => format!("{}XX", range),
+            StatusCode::Default => String::from("default"),
+        }
+    }
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum HttpMethod {
@@ -134,6 +527,22 @@ pub enum HttpMethod {
 }
 
 impl HttpMethod {
+    /// All the methods in the REST-conventional order.
+    pub const ALL: [HttpMethod; 5] = [
+        HttpMethod::get,
+        HttpMethod::post,
+        HttpMethod::put,
+        HttpMethod::patch,
+        HttpMethod::delete,
+    ];
+
+    /// Returns the method by its name, case-insensitive, e.g. `GET`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        return HttpMethod::ALL
+            .into_iter()
+            .find(|method| method.get_value().eq_ignore_ascii_case(name));
+    }
+
     pub fn get_value(&self) -> String {
         return format!("{:?}", &self).to_uppercase();
     }
@@ -174,6 +583,8 @@ pub enum SecuritySchema {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SecuritySchemaBearerToken {
     pub r#type: SecurityType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -181,6 +592,65 @@ pub struct SecuritySchemaApiKey {
     pub r#type: SecurityType,
     pub name: String,
     pub r#in: SecurityTokenLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Metadata about the API.
+/// ref: https://spec.openapis.org/oas/v3.1.0#info-object
+#[derive(Serialize, Deserialize)]
+pub struct Info {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<Contact>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
+}
+
+/// ref: https://spec.openapis.org/oas/v3.1.0#contact-object
+#[derive(Serialize, Deserialize)]
+pub struct Contact {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// ref: https://spec.openapis.org/oas/v3.1.0#license-object
+#[derive(Serialize, Deserialize)]
+pub struct License {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// ref: https://spec.openapis.org/oas/v3.1.0#server-object
+#[derive(Serialize, Deserialize)]
+pub struct Server {
+    /// URL of the server, can contain variables in braces, e.g. `https://{region}.example.com`.
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<IndexMap<String, ServerVariable>>,
+}
+
+impl Server {
+    /// Returns URL of the server with its variables as `{{VARIABLE}}` placeholders.
+    pub fn get_templated_url(&self) -> String {
+        let mut url = self.url.clone();
+        for name in self.variables.iter().flat_map(|variables| variables.keys()) {
+            url = url.replace(&format!("{{{}}}", name), &format!("{{{{{}}}}}", name));
+        }
+        return url;
+    }
+}
+
+/// ref: https://spec.openapis.org/oas/v3.1.0#server-variable-object
+#[derive(Serialize, Deserialize)]
+pub struct ServerVariable {
+    pub default: String,
+    /// Allowed values of the variable.
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub r#enum: Option<Vec<String>>,
 }
 
 /// Holds a set of reusable objects for different aspects of the OAS.
@@ -190,24 +660,270 @@ pub struct SecuritySchemaApiKey {
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Components {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub security_schemes: Option<HashMap<String, SecuritySchema>>,
+    /// Kept as plain values, they are only compared with members of compositions, e.g. with discriminator mapping.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemas: Option<IndexMap<String, Value>>,
+    /// Schemas which are valid schemas, as values of the parsed schemas, so they are comparable
+    /// with the resolved members of compositions. Filled once by `parse_schemas`.
+    #[serde(skip)]
+    pub parsed_schemas: IndexMap<String, Value>,
+}
+
+impl Components {
+    /// Parses the schemas once for the whole schema, instead of for every request comparing them.
+    pub fn parse_schemas(&mut self) {
+        self.parsed_schemas = self
+            .schemas
+            .iter()
+            .flatten()
+            .filter_map(|(name, value)| {
+                let schema = from_value::<Schema>(value.clone()).ok()?;
+                return Some((name.clone(), to_value(schema).ok()?));
+            })
+            .collect();
+    }
+}
+
+/// Describes the operations available on a single path.
+/// ref: https://spec.openapis.org/oas/v3.1.0#path-item-object
+#[derive(Serialize, Deserialize)]
+pub struct PathItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Parameters applicable for all the operations, those are merged into the operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<Parameters>>,
+    #[serde(flatten)]
+    pub operations: IndexMap<HttpMethod, Operation>,
 }
 
+impl PathItem {
+    /// Adds path level parameters to every operation, unless the operation overrides them.
+    fn merge_parameters(&mut self) {
+        let path_parameters = match &self.parameters {
+            Some(parameters) => parameters,
+            None => return,
+        };
+
+        for operation in self.operations.values_mut() {
+            let parameters = operation.parameters.get_or_insert_with(Vec::new);
+            for param in path_parameters {
+                if !parameters
+                    .iter()
+                    .any(|p| p.name == param.name && p.r#in == param.r#in)
+                {
+                    parameters.push(param.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Paths & operations are kept in the order of the schema, so the output is deterministic.
 #[derive(Serialize, Deserialize)]
 pub struct OpenApi {
-    pub paths: HashMap<String, HashMap<HttpMethod, Operation>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<Info>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub servers: Option<Vec<Server>>,
+    /// Missing paths are empty, e.g. in a schema with only shared components.
+    #[serde(default)]
+    pub paths: IndexMap<String, PathItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
+    /// Security requirements applied to all operations which don't declare their own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<HashMap<String, Vec<String>>>>,
+    /// Paths of the schema file and all the files referenced from it.
+    #[serde(skip)]
+    pub documents: Vec<PathBuf>,
 }
 
 impl OpenApi {
-    /// Creates schema and validates it
-    pub fn new(path: &String) -> OpenApi {
-        let data = fs::read_to_string(path).unwrap();
-        let res = from_str(&data);
+    /// Creates schema, resolves all `$ref` references and validates it
+    pub fn new(path: &String) -> Result<OpenApi, AppError> {
+        let path = Path::new(path);
+        let path = path.canonicalize().unwrap_or(path.to_path_buf());
+        let document = load_document(&path)?;
+
+        // the resolved document is a new value, the loaded one is dropped right after
+        let (data, referenced_documents) = resolve_refs(&document, &path)?;
+        drop(document);
+        let mut documents = Vec::from([path.clone()]);
+        documents.extend(referenced_documents);
+        let res = from_value(data);
 
-        match res {
+        let mut schema: OpenApi = match res {
             Ok(schema) => schema,
-            Err(err) => panic!("{:?}", err),
+            Err(err) => return Err(AppError::Parse(err.to_string())),
+        };
+
+        for path_item in schema.paths.values_mut() {
+            path_item.merge_parameters();
+            // explicit empty security of the operation disables the global one
+            if let Some(security) = &schema.security {
+                for operation in path_item.operations.values_mut() {
+                    operation.security.get_or_insert_with(|| security.clone());
+                }
+            }
+        }
+
+        schema.documents = documents;
+        return Ok(schema);
+    }
+
+    /// Removes readOnly properties from `required` of the request bodies & component schemas,
+    /// see `--strip-readonly-required`.
+    pub fn strip_read_only_required(&mut self) {
+        for operation in self.paths.values_mut().flat_map(|item| item.operations.values_mut()) {
+            let media_types = operation
+                .request_body
+                .iter_mut()
+                .flat_map(|body| body.content.values_mut());
+            for schema in media_types.filter_map(|media_type| media_type.schema.as_mut()) {
+                schema.strip_read_only_required();
+            }
+        }
+
+        let schemas = self.components.iter_mut().flat_map(|comps| comps.schemas.iter_mut());
+        schemas.flat_map(|schemas| schemas.values_mut()).for_each(strip_read_only_required);
+    }
+
+    /// Sorts operations of every path by the order of their methods, methods missing in the order go last.
+    pub fn sort_operations(&mut self, method_order: &Vec<HttpMethod>) {
+        let position = |method: &HttpMethod| {
+            return method_order
+                .iter()
+                .position(|ordered| ordered == method)
+                .unwrap_or(method_order.len());
+        };
+        for path_item in self.paths.values_mut() {
+            path_item
+                .operations
+                .sort_by(|a, _, b, _| position(a).cmp(&position(b)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_response_keys() {
+        let schema: OpenApi = from_value(json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "default": { "description": "Error" },
+                            "2XX": { "description": "Success" },
+                            "201": { "description": "Created" },
+                            "200": { "description": "OK" }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let responses = schema.paths["/users"].operations[&HttpMethod::get]
+            .responses
+            .as_ref()
+            .unwrap();
+
+        let mut codes: Vec<StatusCode> = responses.keys().cloned().collect();
+        codes.sort();
+        assert_eq!(
+            codes,
+            [
+                StatusCode::Code(200),
+                StatusCode::Code(201),
+                StatusCode::Range(2),
+                StatusCode::Default
+            ]
+        );
+        assert!(StatusCode::Range(2).is_success());
+        assert!(!StatusCode::Default.is_success());
+    }
+
+    #[test]
+    fn rejects_invalid_response_keys() {
+        for key in ["20", "600", "6XX", "2X", "ok"] {
+            assert!(StatusCode::try_from(String::from(key)).is_err(), "{}", key);
         }
+        assert_eq!(String::from(StatusCode::Range(4)), "4XX");
+    }
+
+    #[test]
+    fn strips_read_only_required() {
+        let mut schema: Schema = from_value(json!({
+            "type": "object",
+            "required": ["id", "name", "owner"],
+            "properties": {
+                "id": { "type": "string", "readOnly": true },
+                "name": { "type": "string" },
+                "owner": {
+                    "type": "object",
+                    "required": ["id", "email"],
+                    "properties": {
+                        "id": { "type": "string", "readOnly": true },
+                        "email": { "type": "string" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        schema.strip_read_only_required();
+        let value = to_value(&schema).unwrap();
+        assert_eq!(value["required"], json!(["name", "owner"]));
+        assert_eq!(value["properties"]["owner"]["required"], json!(["email"]));
+    }
+
+    #[test]
+    fn strips_read_only_required_of_all_of_members() {
+        let mut schema: Schema = from_value(json!({
+            "allOf": [
+                { "type": "object", "required": ["id", "name"] },
+                {
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "string", "readOnly": true },
+                        "name": { "type": "string" }
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+        schema.strip_read_only_required();
+        let value = to_value(&schema).unwrap();
+        assert_eq!(value["allOf"][0]["required"], json!(["name"]));
+    }
+
+    #[test]
+    fn strips_read_only_required_of_components() {
+        let mut schema: OpenApi = from_value(json!({
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["id", "name"],
+                        "properties": {
+                            "id": { "type": "string", "readOnly": true },
+                            "name": { "type": "string", "x-label": "Name" }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        schema.strip_read_only_required();
+        let pet = &schema.components.unwrap().schemas.unwrap()["Pet"];
+        assert_eq!(pet["required"], json!(["name"]));
+        assert_eq!(pet["properties"]["name"]["x-label"], json!("Name"));
     }
 }
@@ -0,0 +1,379 @@
+use crate::{
+    open_api::Info,
+    utils::{apply_line_endings, join_output_path},
+};
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    io::Write,
+    path::Path,
+};
+use zip::{write::FileOptions, ZipWriter};
+
+/// Name of the env file, placed in the root of the output folder, or in every top-level folder.
+pub const ENV_FILE_NAME: &str = "http-client.env.json";
+
+/// Name of the file which marks output folders created by this tool.
+pub const MARKER_FILE_NAME: &str = ".open-api-to-http";
+
+/// Name of the index file, placed in the root of the output folder.
+pub const INDEX_FILE_NAME: &str = "index.md";
+
+/// Name of the shared variables file, placed in the root of the output folder, see `--shared-vars-file`.
+pub const SHARED_VARIABLES_FILE_NAME: &str = "variables.http";
+
+/// Environment which is created when there is no env file yet.
+const DEFAULT_ENV: &str = "dev";
+
+/// File which is planned to be written into the output folder.
+pub struct GeneratedFile {
+    /// Path relative to the output folder, e.g. `/customers/customers.http`.
+    pub path: String,
+    pub content: String,
+}
+
+/// Compares generated files with the content of the output folder
+/// and prints which files would be changed, created or removed.
+///
+/// Returns whenever there are any differences.
+pub fn report_diff(files: &Vec<GeneratedFile>, output_path: &String, crlf: &bool) -> bool {
+    let mut existing_files = HashSet::new();
+    collect_http_files(Path::new(output_path), &String::new(), &mut existing_files);
+
+    let mut differences = Vec::new();
+    for file in files {
+        let content = apply_line_endings(&file.content, crlf);
+        // files next to the requests, e.g. snippets, are not collected, but they could exist already
+        let exists = existing_files.remove(&file.path)
+            || join_output_path(output_path, &file.path).exists();
+        if !exists {
+            differences.push(format!("new: {}", file.path));
+        } else if fs::read_to_string(join_output_path(output_path, &file.path)).ok() != Some(content) {
+            differences.push(format!("changed: {}", file.path));
+        }
+    }
+
+    for path in existing_files {
+        differences.push(format!("removed: {}", path));
+    }
+
+    differences.sort();
+    for difference in &differences {
+        println!("{}", difference);
+    }
+
+    return differences.len() > 0;
+}
+
+/// Collects paths of all `.http` files, the env files & the index file in the folder, relative to the output folder.
+fn collect_http_files(folder: &Path, relative_path: &String, files: &mut HashSet<String>) {
+    let entries = match fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = format!("{}/{}", relative_path, entry.file_name().to_string_lossy());
+        if entry.path().is_dir() {
+            collect_http_files(&entry.path(), &path, files);
+        } else if path.ends_with(".http")
+            || entry.file_name() == ENV_FILE_NAME
+            || path == format!("/{}", INDEX_FILE_NAME)
+        {
+            files.insert(path);
+        }
+    }
+}
+
+/// Function of the HTTP client which percent-encodes the variable, see `--encode-path-params`.
+pub const ENCODE_FUNCTION: &str = "encodeURIComponent";
+
+/// Returns name of the variable of `{{encodeURIComponent(VARIABLE)}}` placeholder, e.g. `id`.
+pub fn get_encoded_variable(placeholder: &str) -> Option<&str> {
+    return placeholder
+        .strip_prefix(ENCODE_FUNCTION)
+        .and_then(|rest| rest.strip_prefix('('))
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map(|variable| variable.trim());
+}
+
+/// Collects names of all `{{VARIABLE}}` placeholders used in the content.
+/// Encoded placeholders, e.g. `{{encodeURIComponent(id)}}`, are collected by their variable.
+pub fn collect_variables(content: &String, variables: &mut BTreeSet<String>) {
+    let mut rest = content.as_str();
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        match rest.find("}}") {
+            Some(end) => {
+                let placeholder = rest[..end].trim();
+                let variable = get_encoded_variable(placeholder).unwrap_or(placeholder);
+                variables.insert(variable.to_owned());
+                rest = &rest[end + 2..];
+            }
+            None => break,
+        }
+    }
+}
+
+/// Creates env file in the folder (relative to the output folder) with all the variables used in the generated files.
+/// Values of an already existing env file in the folder are kept, only missing variables are added,
+/// with their value from `defaults` or empty.
+pub fn create_env_file(
+    variables: &BTreeSet<String>,
+    defaults: &IndexMap<String, String>,
+    output_path: &String,
+    folder: &String,
+) -> GeneratedFile {
+    let env_path = Path::new(output_path)
+        .join(folder.trim_start_matches('/'))
+        .join(ENV_FILE_NAME);
+    let existing_env = fs::read_to_string(env_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Map<String, Value>>(&content).ok());
+
+    let mut envs = existing_env.unwrap_or_else(|| {
+        let mut envs = Map::new();
+        envs.insert(DEFAULT_ENV.to_owned(), json!({}));
+        return envs;
+    });
+
+    for env in envs.values_mut() {
+        if let Value::Object(env) = env {
+            for variable in variables {
+                if !env.contains_key(variable) {
+                    let value = defaults.get(variable).map_or("", |value| value.as_str());
+                    env.insert(variable.clone(), json!(value));
+                }
+            }
+        }
+    }
+
+    return GeneratedFile {
+        path: format!("{}/{}", folder, ENV_FILE_NAME),
+        content: serde_json::to_string_pretty(&envs).unwrap(),
+    };
+}
+
+/// Creates shared variables file with all the variables used in the generated files, as `@VARIABLE = value` lines.
+/// Values of an already existing variables file are kept, dynamic variables (e.g. `{{$uuid}}`) are left out.
+pub fn create_shared_variables_file(variables: &BTreeSet<String>, output_path: &String) -> GeneratedFile {
+    let path = format!("/{}", SHARED_VARIABLES_FILE_NAME);
+    let existing_values: HashMap<String, String> = fs::read_to_string(join_output_path(output_path, &path))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('@'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+        .collect();
+
+    let lines: Vec<String> = variables
+        .iter()
+        .filter(|variable| !variable.starts_with('$'))
+        .map(|variable| match existing_values.get(variable) {
+            Some(value) if value.len() > 0 => format!("@{} = {}", variable, value),
+            _ => format!("@{} =", variable),
+        })
+        .collect();
+
+    return GeneratedFile {
+        path,
+        content: lines.join("\n"),
+    };
+}
+
+/// Returns directive importing the shared variables file into the file, relative to the file,
+/// e.g. `# @import ../variables.http` for `/customers/customers.http`.
+pub fn get_shared_variables_import(path: &String) -> String {
+    let depth = path.trim_start_matches('/').matches('/').count();
+    let prefix = if depth == 0 {
+        "./".to_owned()
+    } else {
+        "../".repeat(depth)
+    };
+    return format!("# @import {}{}", prefix, SHARED_VARIABLES_FILE_NAME);
+}
+
+/// Creates banner of the request files with provenance of the schema, i.e. title & version,
+/// contact & license of the API. Absent fields are left out, None is returned when all of them are absent.
+pub fn create_banner(info: &Info) -> Option<String> {
+    let mut lines = Vec::new();
+
+    let title = [&info.title, &info.version]
+        .into_iter()
+        .flatten()
+        .map(|value| value.as_str())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    if title.len() > 0 {
+        lines.push(format!("# {}", title));
+    }
+    if let Some(name) = info.contact.as_ref().and_then(|contact| contact.name.as_ref()) {
+        lines.push(format!("# Contact: {}", name));
+    }
+    if let Some(name) = info.license.as_ref().and_then(|license| license.name.as_ref()) {
+        lines.push(format!("# License: {}", name));
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+    return Some(lines.join("\n"));
+}
+
+/// Returns top-level folder of the file, e.g. `/customers` for `/customers/orders/orders.http`,
+/// files placed directly in the output folder return an empty string.
+pub fn get_top_folder(path: &String) -> String {
+    return path
+        .trim_start_matches('/')
+        .split_once('/')
+        .map_or(String::new(), |(folder, _)| format!("/{}", folder));
+}
+
+/// Creates markdown index linking all the generated files, grouped by their folder.
+/// Every file is listed with method & path of its requests, links are relative to the output folder.
+///
+/// `files` holds path of the file (relative to the output folder) with request lines of the file.
+pub fn create_index(files: &Vec<(String, Vec<String>)>) -> GeneratedFile {
+    let mut folders = IndexMap::<&str, Vec<String>>::new();
+    for (path, requests) in files {
+        let folder = match path.rfind('/') {
+            Some(0) | None => "/",
+            Some(end) => &path[..end],
+        };
+        let link = path.trim_start_matches('/').replace(' ', "%20");
+        folders.entry(folder).or_insert_with(Vec::new).extend(
+            requests
+                .iter()
+                .map(|request| format!("- [{}]({})", request, link)),
+        );
+    }
+
+    let mut lines = Vec::from(["# Requests".to_owned()]);
+    for (folder, entries) in folders {
+        lines.push(String::new());
+        lines.push(format!("## {}", folder));
+        lines.extend(entries);
+    }
+
+    return GeneratedFile {
+        path: format!("/{}", INDEX_FILE_NAME),
+        content: lines.join("\n"),
+    };
+}
+
+/// Replaces content of `.http` files which is identical to an earlier file with a note referencing that file.
+pub fn dedupe_files(files: &mut Vec<GeneratedFile>) {
+    let mut first_paths = HashMap::new();
+    for file in files.iter_mut() {
+        if !file.path.ends_with(".http") {
+            continue;
+        }
+
+        match first_paths.get(&file.content) {
+            Some(first_path) => file.content = format!("# Same as {}", first_path),
+            None => {
+                first_paths.insert(file.content.clone(), file.path.clone());
+            }
+        }
+    }
+}
+
+/// Cross-checks variables used in the generated files with variables of every environment in the env file.
+/// Returns description of every mismatch, i.e. used variable missing in the env, or env variable which is not used.
+/// Dynamic variables of the HTTP client (e.g. `{{$uuid}}`) are not expected in the env file.
+pub fn validate_placeholders(variables: &BTreeSet<String>, env_file: &GeneratedFile) -> Vec<String> {
+    let envs = match serde_json::from_str::<Map<String, Value>>(&env_file.content) {
+        Ok(envs) => envs,
+        Err(err) => return Vec::from([format!("{} is not valid: {}", env_file.path, err)]),
+    };
+
+    let mut mismatches = Vec::new();
+    for (env_name, env) in &envs {
+        let env_variables: BTreeSet<&String> = match env {
+            Value::Object(env) => env.keys().collect(),
+            _ => BTreeSet::new(),
+        };
+
+        for variable in variables {
+            if !variable.starts_with('$') && !env_variables.contains(variable) {
+                mismatches.push(format!("{{{{{}}}}} is not defined in {} env", variable, env_name));
+            }
+        }
+        for variable in env_variables {
+            if !variables.contains(variable) {
+                mismatches.push(format!("{} of {} env is not used", variable, env_name));
+            }
+        }
+    }
+
+    return mismatches;
+}
+
+/// Writes all the files into a zip archive, keeping the same structure as the output folder.
+pub fn write_bundle(files: &Vec<GeneratedFile>, path: &String, crlf: &bool) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|err| err.to_string())?;
+    let mut zip = ZipWriter::new(file);
+
+    for generated_file in files {
+        zip.start_file(generated_file.path.trim_start_matches('/'), FileOptions::default())
+            .map_err(|err| err.to_string())?;
+        zip.write_all(apply_line_endings(&generated_file.content, crlf).as_bytes())
+            .map_err(|err| err.to_string())?;
+    }
+
+    zip.finish().map_err(|err| err.to_string())?;
+    return Ok(());
+}
+
+/// Removes the folders (relative to the output folder) which are empty, nested folders go first,
+/// so their parent is removed as well when it's left empty. Other folders are never touched.
+pub fn remove_empty_folders(folders: &Vec<String>, output_path: &String) -> Result<(), String> {
+    let mut folders: Vec<&String> = folders.iter().collect::<BTreeSet<&String>>().into_iter().collect();
+    folders.sort_by_key(|folder| std::cmp::Reverse(folder.matches('/').count()));
+
+    for folder in folders {
+        let path = join_output_path(output_path, folder);
+        let is_empty = match fs::read_dir(&path) {
+            Ok(mut entries) => entries.next().is_none(),
+            Err(_) => false,
+        };
+        if is_empty {
+            fs::remove_dir(&path)
+                .map_err(|err| format!("{} could not be removed: {}", path.display(), err))?;
+        }
+    }
+
+    return Ok(());
+}
+
+/// Removes all the content of the output folder, except the env file, the shared variables file & the marker file.
+/// Only folders marked as created by this tool are cleaned, unless `force` is set.
+pub fn clean_output(output_path: &String, force: &bool) -> Result<(), String> {
+    let output_dir = Path::new(output_path);
+    if !force && !output_dir.join(MARKER_FILE_NAME).exists() {
+        return Err(format!(
+            "{} was not generated by this tool, use --force to clean it anyway",
+            output_path
+        ));
+    }
+
+    let entries = fs::read_dir(output_dir).map_err(|err| err.to_string())?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if name == ENV_FILE_NAME || name == SHARED_VARIABLES_FILE_NAME || name == MARKER_FILE_NAME {
+            continue;
+        }
+
+        let path = entry.path();
+        let res = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        res.map_err(|err| format!("{} could not be removed: {}", path.display(), err))?;
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,341 @@
+use crate::{error::AppError, logging::warn_at};
+use flate2::read::GzDecoder;
+use serde_json::{from_reader, json, Map, Value};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+/// First bytes of every gzip file.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Checks whenever the document is YAML by its extension, e.g. `openapi.yaml` or `openapi.yml.gz`.
+fn is_yaml(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+    let name = name.strip_suffix(".gz").unwrap_or(&name);
+    return name.ends_with(".yaml") || name.ends_with(".yml");
+}
+
+/// Loads JSON or YAML (by its extension, see `is_yaml`) document from provided path.
+/// The document is parsed directly from the buffered file, so it's not held in memory twice.
+/// Gzip-compressed documents, i.e. with `.gz` extension or gzip magic bytes, are decompressed on the fly.
+pub fn load_document(path: &Path) -> Result<Value, AppError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(AppError::Io(format!(
+                "Unable to read {}: {}",
+                path.display(),
+                err
+            )))
+        }
+    };
+
+    let mut reader = BufReader::new(file);
+    let is_gzip = path.extension().is_some_and(|extension| extension == "gz")
+        || reader
+            .fill_buf()
+            .is_ok_and(|buffer| buffer.starts_with(&GZIP_MAGIC));
+    let reader: Box<dyn Read> = if is_gzip {
+        Box::new(GzDecoder::new(reader))
+    } else {
+        Box::new(reader)
+    };
+
+    let document = if is_yaml(path) {
+        serde_yaml::from_reader(reader).map_err(|err| err.to_string())
+    } else {
+        from_reader(reader).map_err(|err| err.to_string())
+    };
+    return document
+        .map_err(|err| AppError::Parse(format!("Unable to parse {}: {}", path.display(), err)));
+}
+
+/// Loads JSON example of `externalValue`, either from the URL, or from the path relative to the document.
+/// Fetching from the URL fails with `AppError::Network` when it takes more than `timeout` seconds,
+/// see `--fetch-timeout`.
+pub fn fetch_example(
+    location: &String,
+    document_path: &Path,
+    timeout: u64,
+) -> Result<Value, AppError> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(timeout))
+            .build();
+        let get_error = |err: &(dyn Error + 'static)| {
+            if is_timeout(err) {
+                return AppError::Network(format!(
+                    "Fetching of {} timed out after {}s, see --fetch-timeout",
+                    location, timeout
+                ));
+            }
+            return AppError::Io(format!("Unable to fetch {}: {}", location, err));
+        };
+        let response = agent.get(location).call().map_err(|err| get_error(&err))?;
+        return from_reader(response.into_reader()).map_err(|err| get_error(&err));
+    }
+
+    let path = location.trim_start_matches("file://");
+    let path = document_path.parent().unwrap_or(Path::new("")).join(path);
+    return load_document(&path);
+}
+
+/// Checks whenever the error, or any of its sources, is a timeout.
+fn is_timeout(err: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<io::Error>() {
+            if err.kind() == io::ErrorKind::TimedOut {
+                return true;
+            }
+        }
+        source = err.source();
+    }
+    return false;
+}
+
+/// Creates copy of the document with all `$ref` objects replaced with the values they are pointing to.
+/// Supports both local references (`#/components/schemas/User`)
+/// and references to other files relative to the current document (`./schemas/user.json#/User`).
+///
+/// The document is only borrowed, references are resolved against it in place, without copying it.
+/// Other documents are loaded once, no matter how many references point to them.
+///
+/// Circular references are replaced with an empty object schema to prevent infinite expansion.
+///
+/// Returns the resolved document with paths of all the other documents which were referenced.
+pub fn resolve_refs(document: &Value, document_path: &Path) -> Result<(Value, Vec<PathBuf>), AppError> {
+    let mut stack = Vec::new();
+    let mut cache = HashMap::new();
+    let mut documents = Vec::new();
+    let resolved = resolve(
+        document,
+        document,
+        document_path,
+        &mut stack,
+        &mut cache,
+        &mut documents,
+    )?;
+    return Ok((resolved, documents));
+}
+
+/// Returns resolved copy of the value, `document` is the document of the value, which local references point to.
+/// `cache` holds the other documents by their canonical path.
+fn resolve(
+    value: &Value,
+    document: &Value,
+    document_path: &Path,
+    stack: &mut Vec<String>,
+    cache: &mut HashMap<PathBuf, Rc<Value>>,
+    documents: &mut Vec<PathBuf>,
+) -> Result<Value, AppError> {
+    if let Some(reference) = value.get("$ref").and_then(|r| r.as_str()) {
+        let (file, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+
+        // other document is shared with the cache, so it's not loaded again by the next reference
+        let other_document = if file.is_empty() {
+            None
+        } else {
+            let path = get_relative_path(document_path, file);
+            let other_document = match cache.get(&path) {
+                Some(other_document) => other_document.clone(),
+                None => {
+                    let other_document = Rc::new(load_document(&path)?);
+                    cache.insert(path.clone(), other_document.clone());
+                    documents.push(path.clone());
+                    other_document
+                }
+            };
+            Some((other_document, path))
+        };
+        let (target_document, target_path) = match &other_document {
+            Some((other_document, path)) => (other_document.as_ref(), path.as_path()),
+            None => (document, document_path),
+        };
+
+        let key = format!("{}#{}", target_path.display(), fragment);
+        if stack.contains(&key) {
+            warn_at(
+                &format!("circular reference {} was not expanded", reference),
+                &[("reference", &reference.to_owned())],
+            );
+            return Ok(json!({ "type": "object" }));
+        }
+
+        // `~0` & `~1` escapes are decoded by the pointer itself
+        let target = match target_document.pointer(&percent_decode(fragment)) {
+            Some(target) => target,
+            None => {
+                return Err(AppError::Validation(format!(
+                    "Reference {} was not found",
+                    reference
+                )))
+            }
+        };
+
+        stack.push(key);
+        let resolved = resolve(target, target_document, target_path, stack, cache, documents)?;
+        stack.pop();
+
+        return Ok(resolved);
+    }
+
+    match value {
+        Value::Object(map) => {
+            let mut resolved = Map::with_capacity(map.len());
+            for (key, child) in map {
+                let child = resolve(child, document, document_path, stack, cache, documents)?;
+                resolved.insert(key.clone(), child);
+            }
+            return Ok(Value::Object(resolved));
+        }
+        Value::Array(items) => {
+            let mut resolved = Vec::with_capacity(items.len());
+            for child in items {
+                resolved.push(resolve(child, document, document_path, stack, cache, documents)?);
+            }
+            return Ok(Value::Array(resolved));
+        }
+        value => return Ok(value.clone()),
+    }
+}
+
+/// Decodes percent-encoded characters of the URI fragment, e.g. `%7B` -> `{`.
+/// The fragment is returned unchanged when it's not a valid encoding.
+fn percent_decode(fragment: &str) -> String {
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = fragment
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = hex {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    return String::from_utf8(decoded).unwrap_or_else(|_| fragment.to_owned());
+}
+
+/// Resolves path of the referenced file relative to the directory of the current document.
+fn get_relative_path(document_path: &Path, file: &str) -> PathBuf {
+    let path = document_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(file);
+
+    return path.canonicalize().unwrap_or(path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::{env, fs, io::Write};
+
+    #[test]
+    fn decodes_escaped_pointer_fragments() {
+        let document = json!({
+            "components": {
+                "schemas": {
+                    "orders/item": { "type": "string" },
+                    "a~b": { "type": "integer" },
+                    "{id}": { "type": "boolean" }
+                }
+            },
+            "item": { "$ref": "#/components/schemas/orders~1item" },
+            "tilde": { "$ref": "#/components/schemas/a~0b" },
+            "encoded": { "$ref": "#/components/schemas/%7Bid%7D" }
+        });
+
+        let (resolved, documents) = resolve_refs(&document, Path::new("schema.json")).unwrap();
+
+        assert_eq!(resolved["item"], json!({ "type": "string" }));
+        assert_eq!(resolved["tilde"], json!({ "type": "integer" }));
+        assert_eq!(resolved["encoded"], json!({ "type": "boolean" }));
+        assert!(documents.is_empty());
+    }
+
+    #[test]
+    fn loads_gzip_compressed_document() {
+        let document = json!({ "openapi": "3.0.0", "paths": {} });
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(document.to_string().as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        // gzip is detected by the extension, or by the magic bytes of the content
+        let folder = env::temp_dir().join(format!("open-api-to-http-gzip-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        let gzip_path = folder.join("schema.json.gz");
+        let magic_path = folder.join("compressed.json");
+        let plain_path = folder.join("schema.json");
+        fs::write(&gzip_path, &compressed).unwrap();
+        fs::write(&magic_path, &compressed).unwrap();
+        fs::write(&plain_path, document.to_string()).unwrap();
+
+        let loaded =
+            [&gzip_path, &magic_path, &plain_path].map(|path| load_document(path).unwrap());
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(loaded, [document.clone(), document.clone(), document]);
+    }
+
+    #[test]
+    fn loads_yaml_document() {
+        let folder = env::temp_dir().join(format!("open-api-to-http-yaml-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("schema.yml");
+        let content = [
+            "openapi: 3.0.0",
+            "paths:",
+            "  /users:",
+            "    get:",
+            "      responses:",
+            "        200:",
+            "          description: OK",
+        ];
+        fs::write(&path, content.join("\n")).unwrap();
+
+        let loaded = load_document(&path);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(
+            loaded.unwrap(),
+            json!({
+                "openapi": "3.0.0",
+                "paths": { "/users": { "get": { "responses": { "200": { "description": "OK" } } } } }
+            })
+        );
+    }
+
+    #[test]
+    fn fetch_times_out_without_response() {
+        // connection is accepted by the backlog, but nothing is ever sent back
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let location = format!("http://{}/example.json", listener.local_addr().unwrap());
+
+        let err = fetch_example(&location, Path::new("schema.json"), 1).unwrap_err();
+
+        assert_eq!(err.exit_code(), exitcode::UNAVAILABLE);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Network error: Fetching of {} timed out after 1s, see --fetch-timeout",
+                location
+            )
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{
+    app::Config,
+    open_api::{PrimitiveType, Schema},
+};
+use serde_json::{json, Value};
+
+const INDENT: &str = "  ";
+
+/// Placeholder key of the sample entry of `patternProperties`.
+pub const PATTERN_KEY: &str = "<key>";
+
+/// How readOnly properties are handled in the request bodies.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReadOnlyMode {
+    /// Properties are left out, since they are not sent in requests.
+    Omit,
+    /// Properties are kept as commented out lines, e.g. `// "id": 0,`.
+    Comment,
+}
+
+impl ReadOnlyMode {
+    /// All the modes, in order they are listed in the help.
+    pub const ALL: [ReadOnlyMode; 2] = [ReadOnlyMode::Omit, ReadOnlyMode::Comment];
+
+    /// Returns the mode by its name, e.g. `omit`.
+    pub fn from_name(name: &String) -> Option<Self> {
+        return ReadOnlyMode::ALL.into_iter().find(|mode| mode.get_name() == name);
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ReadOnlyMode::Omit => "omit",
+            ReadOnlyMode::Comment => "comment",
+        }
+    }
+}
+
+/// Creates JSON skeleton of the request body from provided schema,
+/// every property is filled with its example, its default, or with an empty value of its type.
+///
+/// # Examples
+///
+/// ```text
+/// // for schema with properties "name" (string) & "age" (integer)
+/// // this would return the following string
+/// // {
+/// //   "name": "",
+/// //   "age": 0
+/// // }
+/// let skeleton = create_skeleton(&schema, &config);
+/// ```
+pub fn create_skeleton(schema: &Schema, config: &Config) -> String {
+    return format_value(schema, 0, config);
+}
+
+/// Creates formatted JSON from the example of the request body.
+/// With `--redact`, values of sensitive fields are replaced with variables.
+pub fn format_example(example: &Value, config: &Config) -> String {
+    return format_json(&redact_example(example, config), 0);
+}
+
+/// Creates sample body of JSON Patch (RFC 6902) with a single `replace` operation,
+/// the patched field is not known from the schema of the operations, so it's a placeholder.
+pub fn create_json_patch_sample(config: &Config) -> String {
+    let sample = json!([{ "op": "replace", "path": "/field", "value": "" }]);
+    return format_example(&sample, config);
+}
+
+/// Replaces values of sensitive fields in the example with variables, when `--redact` is set.
+fn redact_example(example: &Value, config: &Config) -> Value {
+    match example {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    if config.should_redact(key) {
+                        return (key.clone(), json!(format!("{{{{{}}}}}", key)));
+                    }
+                    return (key.clone(), redact_example(value, config));
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| redact_example(item, config))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Formats JSON value on multiple lines, nested lines are indented to the provided level.
+fn format_json(value: &Value, indent: usize) -> String {
+    return serde_json::to_string_pretty(value)
+        .unwrap()
+        .lines()
+        .collect::<Vec<&str>>()
+        .join(&format!("\n{}", INDENT.repeat(indent)));
+}
+
+/// Collects properties of the schema, members of allOf are merged together (first declaration of a property wins),
+/// for anyOf & oneOf only the first member is used, nested compositions are collected recursively.
+/// readOnly properties are left out, since they are not sent in requests, unless they are commented with `--readonly-mode`.
+/// Deprecated properties are left out with `--exclude-deprecated`,
+/// properties are sorted by name with `--sort-properties`.
+fn get_properties<'a>(schema: &'a Schema, config: &Config) -> Vec<(&'a String, &'a Schema)> {
+    let mut props: Vec<(&String, &Schema)> = match schema {
+        Schema::Object(obj) => match &obj.properties {
+            Some(properties) => properties
+                .iter()
+                .filter(|(_, prop)| {
+                    config.read_only_mode == ReadOnlyMode::Comment || !prop.is_read_only()
+                })
+                .filter(|(_, prop)| !(config.exclude_deprecated && prop.is_deprecated()))
+                .collect(),
+            None => Vec::new(),
+        },
+        Schema::AllOf { allOf } => {
+            // property declared by more members is listed only once
+            let mut props: Vec<(&String, &Schema)> = Vec::new();
+            for (name, prop) in allOf.iter().flat_map(|member| get_properties(member, config)) {
+                if !props.iter().any(|(existing, _)| *existing == name) {
+                    props.push((name, prop));
+                }
+            }
+            props
+        }
+        Schema::AnyOf { anyOf: members } | Schema::OneOf { oneOf: members, .. } => members
+            .first()
+            .map(|member| get_properties(member, config))
+            .unwrap_or_default(),
+        Schema::Not { not: _ } => Vec::new(),
+    };
+
+    if config.sort_properties {
+        props.sort_by_key(|(a, _)| *a);
+    }
+    return props;
+}
+
+/// Returns type of the schema, composition (e.g. oneOf integer & number) uses type of the first member.
+fn get_type(schema: &Schema) -> &PrimitiveType {
+    match schema {
+        Schema::Object(obj) => &obj.r#type,
+        Schema::AllOf { allOf: members }
+        | Schema::AnyOf { anyOf: members }
+        | Schema::OneOf { oneOf: members, .. } => members
+            .first()
+            .map(get_type)
+            .unwrap_or(&PrimitiveType::Object),
+        Schema::Not { not: _ } => &PrimitiveType::Object,
+    }
+}
+
+/// Creates formatted JSON value of the schema, objects are expanded into multiple lines.
+fn format_value(schema: &Schema, indent: usize, config: &Config) -> String {
+    if let Some(constant) = schema.get_const() {
+        return serde_json::to_string(constant).unwrap();
+    }
+
+    if let Some(example) = schema.get_example() {
+        return format_json(&redact_example(example, config), indent);
+    }
+
+    // default keeps its own type, e.g. `true` or `5`
+    if let Some(default) = schema.get_default() {
+        return format_json(&redact_example(default, config), indent);
+    }
+
+    // comments keep the precise type, both numeric types share the same empty value
+    match get_type(schema) {
+        PrimitiveType::String => "\"\"".to_owned(),
+        PrimitiveType::Number | PrimitiveType::Integer => "0".to_owned(),
+        PrimitiveType::Boolean => "false".to_owned(),
+        PrimitiveType::Array => "[]".to_owned(),
+        PrimitiveType::Object => {
+            let pattern_key = String::from(PATTERN_KEY);
+            let mut props = get_properties(schema, config);
+            if let Some(value) = get_pattern_property(schema) {
+                props.push((&pattern_key, value));
+            }
+            format_object(&props, indent, config)
+        }
+    }
+}
+
+/// Returns value schema of the first `patternProperties` entry, which is used for a sample entry of the map.
+/// Every entry would have the same placeholder key, so the other patterns are noted only in the comments.
+fn get_pattern_property(schema: &Schema) -> Option<&Schema> {
+    match schema {
+        Schema::Object(obj) => obj
+            .pattern_properties
+            .as_ref()
+            .and_then(|props| props.values().next()),
+        _ => None,
+    }
+}
+
+/// Creates formatted JSON value of the property,
+/// with `--redact`, example or default of a sensitive property is replaced with a variable.
+fn format_property_value(name: &String, schema: &Schema, indent: usize, config: &Config) -> String {
+    let has_value = schema.get_example().or(schema.get_default()).is_some();
+    if has_value && config.should_redact(name) {
+        return format!("\"{{{{{}}}}}\"", name);
+    }
+    return format_value(schema, indent, config);
+}
+
+fn format_object(props: &Vec<(&String, &Schema)>, indent: usize, config: &Config) -> String {
+    if props.len() == 0 {
+        return "{}".to_owned();
+    }
+
+    let mut lines = Vec::from(["{".to_owned()]);
+    for (i, (name, schema)) in props.iter().enumerate() {
+        // commented out properties are not part of the JSON, so they don't need the separator of the last property
+        let is_commented = schema.is_read_only();
+        let is_followed = props[i + 1..].iter().any(|(_, next)| !next.is_read_only());
+        let separator = if is_followed || (is_commented && i < props.len() - 1) {
+            ","
+        } else {
+            ""
+        };
+        let value = format!(
+            "{}{}: {}{}",
+            INDENT.repeat(indent + 1),
+            serde_json::to_string(name).unwrap(),
+            format_property_value(name, schema, indent + 1, config),
+            separator
+        );
+
+        let mut value_lines = value.lines();
+        let mut first_line = value_lines.next().unwrap().to_owned();
+        if config.json_body_comments {
+            first_line.push_str(&format!(" // {}", get_property_comment(schema)));
+        }
+        lines.push(first_line);
+        lines.extend(value_lines.map(|line| line.to_owned()));
+
+        if is_commented {
+            let start = lines.len() - value.lines().count();
+            let prefix = INDENT.repeat(indent + 1);
+            for line in &mut lines[start..] {
+                let content = line.strip_prefix(&prefix).unwrap_or(line);
+                *line = format!("{}// {}", prefix, content);
+            }
+        }
+    }
+    lines.push(format!("{}}}", INDENT.repeat(indent)));
+
+    return lines.join("\n");
+}
+
+/// Creates `Type — description` comment for the property.
+fn get_property_comment(schema: &Schema) -> String {
+    let mut comment = schema
+        .get_all_types()
+        .iter()
+        .map(|p_type| p_type.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+
+    if let Schema::Object(obj) = schema {
+        if let Some(desc) = &obj.description {
+            comment.push_str(&format!(" — {}", desc));
+        }
+    }
+
+    return comment;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_fields_are_zero_with_precise_types() {
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "count": { "type": "integer" },
+                "price": { "type": "number", "format": "float" },
+                "amount": { "oneOf": [{ "type": "integer" }, { "type": "number" }] }
+            }
+        }))
+        .unwrap();
+        let config = Config::from_test_args(&["--json-body-comments"]);
+
+        assert_eq!(
+            create_skeleton(&schema, &config),
+            [
+                "{",
+                "  \"count\": 0, // Integer",
+                "  \"price\": 0, // Number",
+                "  \"amount\": 0 // Number,Integer",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+}
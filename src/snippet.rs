@@ -0,0 +1,236 @@
+use crate::output::get_encoded_variable;
+
+/// Languages of the client snippets, which can be generated next to the requests.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SnippetLanguage {
+    /// Python with the `requests` library.
+    PythonRequests,
+    /// JavaScript with the built-in `fetch`.
+    JavascriptFetch,
+}
+
+impl SnippetLanguage {
+    /// All the languages, in order they are listed in the help.
+    pub const ALL: [SnippetLanguage; 2] = [
+        SnippetLanguage::PythonRequests,
+        SnippetLanguage::JavascriptFetch,
+    ];
+
+    /// Returns the language by its name, e.g. `python-requests`.
+    pub fn from_name(name: &String) -> Option<Self> {
+        return SnippetLanguage::ALL
+            .into_iter()
+            .find(|language| language.get_name() == name);
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SnippetLanguage::PythonRequests => "python-requests",
+            SnippetLanguage::JavascriptFetch => "javascript-fetch",
+        }
+    }
+
+    /// Returns extension of the snippet files, which replaces `.http` of the request files.
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            SnippetLanguage::PythonRequests => ".py",
+            SnippetLanguage::JavascriptFetch => ".js",
+        }
+    }
+
+    /// Returns beginning of every snippet file, e.g. imports.
+    pub fn get_prelude(&self) -> String {
+        match self {
+            SnippetLanguage::PythonRequests => "import os\n\nimport requests".to_owned(),
+            SnippetLanguage::JavascriptFetch => String::new(),
+        }
+    }
+}
+
+/// Creates code calling the request in the language.
+/// Variables of the request, e.g. `{{TOKEN}}`, are read from environment variables of the same name.
+///
+/// `request_line` is used as a comment describing the snippet, e.g. `GET /customers`.
+pub fn create_snippet(
+    language: &SnippetLanguage,
+    request_line: &String,
+    method: &String,
+    url: &String,
+    headers: &Vec<(String, String)>,
+    body: Option<&String>,
+) -> String {
+    match language {
+        SnippetLanguage::PythonRequests => {
+            create_python_snippet(request_line, method, url, headers, body)
+        }
+        SnippetLanguage::JavascriptFetch => {
+            create_javascript_snippet(request_line, method, url, headers, body)
+        }
+    }
+}
+
+fn create_python_snippet(
+    request_line: &String,
+    method: &String,
+    url: &String,
+    headers: &Vec<(String, String)>,
+    body: Option<&String>,
+) -> String {
+    let mut lines = Vec::from([
+        format!("# {}", request_line),
+        "response = requests.request(".to_owned(),
+        format!("    {},", quote(method)),
+        format!("    f\"{}\",", to_python(url)),
+    ]);
+
+    if headers.len() > 0 {
+        lines.push("    headers={".to_owned());
+        for (name, value) in headers {
+            lines.push(format!("        {}: f\"{}\",", quote(name), to_python(value)));
+        }
+        lines.push("    },".to_owned());
+    }
+
+    if let Some(body) = body {
+        // triple quotes keep the body on multiple lines, as in the request file
+        lines.push(format!("    data=f\"\"\"{}\"\"\",", to_python(body)));
+    }
+
+    lines.push(")".to_owned());
+    lines.push("print(response.status_code, response.text)".to_owned());
+    return lines.join("\n");
+}
+
+fn create_javascript_snippet(
+    request_line: &String,
+    method: &String,
+    url: &String,
+    headers: &Vec<(String, String)>,
+    body: Option<&String>,
+) -> String {
+    let mut lines = Vec::from([
+        format!("// {}", request_line),
+        format!("fetch(`{}`, {{", to_javascript(url)),
+        format!("  method: {},", quote(method)),
+    ]);
+
+    if headers.len() > 0 {
+        lines.push("  headers: {".to_owned());
+        for (name, value) in headers {
+            lines.push(format!("    {}: `{}`,", quote(name), to_javascript(value)));
+        }
+        lines.push("  },".to_owned());
+    }
+
+    if let Some(body) = body {
+        lines.push(format!("  body: `{}`,", to_javascript(body)));
+    }
+
+    lines.push("})".to_owned());
+    lines.push("  .then((response) => response.text())".to_owned());
+    lines.push("  .then(console.log);".to_owned());
+    return lines.join("\n");
+}
+
+/// Creates curl command of the request on a single line, so it can be placed into a comment.
+/// Variables are kept as `{{VARIABLE}}` placeholders, multi-line bodies are joined into one line.
+pub fn create_curl_command(
+    method: &String,
+    url: &String,
+    headers: &Vec<(String, String)>,
+    body: Option<&String>,
+) -> String {
+    let mut parts = Vec::from([
+        "curl".to_owned(),
+        format!("-X {}", method),
+        quote_shell(url),
+    ]);
+
+    for (name, value) in headers {
+        parts.push(format!("-H {}", quote_shell(&format!("{}: {}", name, value))));
+    }
+
+    if let Some(body) = body {
+        let body = match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(value) => value.to_string(),
+            Err(_) => body
+                .lines()
+                .map(|line| line.trim())
+                .collect::<Vec<&str>>()
+                .join(" "),
+        };
+        parts.push(format!("--data {}", quote_shell(&body)));
+    }
+
+    return parts.join(" ");
+}
+
+/// Returns the value as a single-quoted shell argument.
+fn quote_shell(value: &String) -> String {
+    return format!("'{}'", value.replace('\'', "'\\''"));
+}
+
+/// Returns the value as a double-quoted string literal, which is same for both languages.
+fn quote(value: &String) -> String {
+    return serde_json::to_string(value).unwrap();
+}
+
+/// Escapes the value for Python f-string, variables are read with `os.environ`.
+/// Encoded variables are percent-encoded with `quote` of the `requests` library.
+fn to_python(value: &String) -> String {
+    return replace_variables(
+        value,
+        |text| {
+            text.replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('{', "{{")
+                .replace('}', "}}")
+        },
+        |variable| match get_encoded_variable(variable) {
+            Some(variable) => format!("{{requests.utils.quote(os.environ['{}'], safe='')}}", variable),
+            None => format!("{{os.environ['{}']}}", variable),
+        },
+    );
+}
+
+/// Escapes the value for JavaScript template literal, variables are read with `process.env`.
+fn to_javascript(value: &String) -> String {
+    return replace_variables(
+        value,
+        |text| {
+            text.replace('\\', "\\\\")
+                .replace('`', "\\`")
+                .replace("${", "\\${")
+        },
+        |variable| match get_encoded_variable(variable) {
+            Some(variable) => format!(
+                "${{encodeURIComponent(process.env[{}])}}",
+                quote(&variable.to_owned())
+            ),
+            None => format!("${{process.env[{}]}}", quote(&variable.to_owned())),
+        },
+    );
+}
+
+/// Escapes the text around `{{VARIABLE}}` placeholders with `escape`
+/// and replaces the placeholders with the result of `variable`.
+fn replace_variables<E, V>(value: &String, escape: E, variable: V) -> String
+where
+    E: Fn(&str) -> String,
+    V: Fn(&str) -> String,
+{
+    let mut output = String::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        output.push_str(&escape(&rest[..start]));
+        output.push_str(&variable(rest[start + 2..end].trim()));
+        rest = &rest[end + 2..];
+    }
+    output.push_str(&escape(rest));
+
+    return output;
+}
@@ -1,35 +1,164 @@
-use std::fs::OpenOptions;
+use crate::error::AppError;
+use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
-use std::{fs, io, path::Path};
+use std::process::Command;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
 
-fn create_folder_if_not_exists(name: &String) -> Result<(), io::Error> {
-    if !Path::new(name).exists() {
-        fs::create_dir(name)?;
+fn create_folder_if_not_exists(path: &Path) -> Result<(), io::Error> {
+    if !path.exists() {
+        fs::create_dir(path)?;
     }
     Ok(())
 }
 
+/// Expands leading `~` to the home folder and `$VAR` or `${VAR}` to the value of the environment variable,
+/// e.g. `~/apis/$API` -> `/home/user/apis/users`. Variables which are not set are kept as they are.
+pub fn expand_path(path: &String) -> String {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path.as_str();
+    if rest == "~" || rest.starts_with("~/") || rest.starts_with("~\\") {
+        if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+            expanded.push_str(&home);
+            rest = &rest[1..];
+        }
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, end) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+
+        match env::var(name).ok().filter(|_| name.len() > 0) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..start + 1 + end]),
+        }
+        rest = &after[end..];
+    }
+    expanded.push_str(rest);
+
+    return expanded;
+}
+
+/// Joins the output folder with a path relative to it, e.g. `/customers/customers.http`.
+/// Relative path always uses `/` as separator, it's converted to the separator of the platform.
+pub fn join_output_path(output_path: &String, relative_path: &String) -> PathBuf {
+    let mut path = PathBuf::from(output_path);
+    for segment in relative_path.split('/').filter(|segment| segment.len() > 0) {
+        path.push(segment);
+    }
+    return path;
+}
+
 /// Creates provided folders in order at specified path.
-pub fn create_folders(folders: &Vec<String>, output_path: &String) {
+pub fn create_folders(folders: &Vec<String>, output_path: &String) -> Result<(), AppError> {
     for folder in folders {
-        let path = format!("{}/{}", output_path, folder);
-        create_folder_if_not_exists(&path).unwrap();
+        let path = join_output_path(output_path, folder);
+        create_folder_if_not_exists(&path).map_err(|err| {
+            AppError::Io(format!("Unable to create {}: {}", path.display(), err))
+        })?;
     }
+    return Ok(());
 }
 
 /// Creates file and writes all provided data.
-pub fn create_file(data: &String, path: &String) {
-    let mut file = OpenOptions::new()
+/// Data are expected to use `\n` line endings, which are translated to `\r\n` when `crlf` is set.
+pub fn create_file(data: &String, path: &Path, crlf: &bool) -> Result<(), AppError> {
+    let file = OpenOptions::new()
         .create(true)
         .write(true)
         .truncate(true)
         .open(path)
-        .unwrap();
+        .map_err(|err| AppError::Io(format!("Unable to create {}: {}", path.display(), err)))?;
+
+    return write_data(file, data, path, crlf);
+}
+
+/// Appends provided data at the end of the existing file.
+pub fn append_file(data: &String, path: &Path, crlf: &bool) -> Result<(), AppError> {
+    let file = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .map_err(|err| AppError::Io(format!("Unable to open {}: {}", path.display(), err)))?;
+
+    return write_data(file, data, path, crlf);
+}
 
-    let res = file.write_all(data.as_bytes());
+/// Translates `\n` line endings of the data to `\r\n` when `crlf` is set.
+pub fn apply_line_endings(data: &String, crlf: &bool) -> String {
+    if *crlf {
+        return data.replace("\n", "\r\n");
+    }
+    return data.clone();
+}
+
+fn write_data(mut file: File, data: &String, path: &Path, crlf: &bool) -> Result<(), AppError> {
+    let res = file.write_all(apply_line_endings(data, crlf).as_bytes());
 
     match res {
-        Ok(_) => (),
-        Err(err) => panic!("{}", err),
+        Ok(_) => Ok(()),
+        Err(err) => Err(AppError::Io(format!(
+            "Unable to write {}: {}",
+            path.display(),
+            err
+        ))),
+    }
+}
+
+/// Runs the command with the path appended as its last argument.
+/// Returns error message when the command couldn't be started or exited with non-zero code.
+pub fn run_command(command: &String, path: &Path) -> Result<(), String> {
+    let mut parts = command.split_whitespace();
+    let program = match parts.next() {
+        Some(program) => program,
+        None => return Err(String::from("command is empty")),
+    };
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|err| format!("{} could not be started: {}", program, err))?;
+
+    if !status.success() {
+        return Err(format!("{} exited with {}", command, status));
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_relative_output_path() {
+        let path = join_output_path(&String::from("out"), &String::from("/customers/orders.http"));
+
+        assert_eq!(path, Path::new("out").join("customers").join("orders.http"));
+    }
+
+    #[test]
+    fn joins_absolute_output_path() {
+        let output_path = env::temp_dir().join("requests");
+        let path = join_output_path(
+            &output_path.display().to_string(),
+            &String::from("/customers/orders.http"),
+        );
+
+        assert!(path.is_absolute());
+        assert_eq!(path, output_path.join("customers").join("orders.http"));
     }
 }
@@ -0,0 +1,93 @@
+use crate::{
+    error::AppError,
+    logging::{error_at, progress, warn},
+};
+use notify::{recommended_watcher, RecursiveMode, Watcher};
+use std::{
+    collections::BTreeSet,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::channel,
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Time without further changes after which the files are regenerated.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How often the interruption with Ctrl-C is checked.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Runs `generate` and then again on every change of the watched files, until Ctrl-C is pressed.
+/// `generate` returns files which should be watched from then on, when it fails
+/// the error is reported and the previous files are still watched.
+///
+/// Rapid changes are debounced, so e.g. saving a file multiple times regenerates only once.
+pub fn watch<F>(files: Vec<PathBuf>, mut generate: F) -> Result<(), AppError>
+where
+    F: FnMut() -> Result<Vec<PathBuf>, AppError>,
+{
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let handler_interrupted = interrupted.clone();
+    ctrlc::set_handler(move || handler_interrupted.store(true, Ordering::SeqCst))
+        .map_err(|err| AppError::Io(format!("Unable to handle Ctrl-C: {}", err)))?;
+
+    let (sender, receiver) = channel();
+    let mut watcher = recommended_watcher(sender)
+        .map_err(|err| AppError::Io(format!("Unable to watch files: {}", err)))?;
+
+    let mut files = files;
+    let mut watched_folders = BTreeSet::new();
+    // first generation runs right away
+    let mut deadline = Some(Instant::now());
+
+    while !interrupted.load(Ordering::SeqCst) {
+        if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+            deadline = None;
+
+            let started = Instant::now();
+            match generate() {
+                Ok(generated_files) => {
+                    files = generated_files;
+                    progress(
+                        "watch",
+                        &format!(
+                            "regenerated in {}ms, watching {} files",
+                            started.elapsed().as_millis(),
+                            files.len()
+                        ),
+                    );
+                }
+                Err(err) => error_at(&err.to_string(), &[("source", &String::from("watch"))]),
+            }
+
+            // folders are watched instead of the files, since editors often replace the file on save
+            for file in &files {
+                let folder = match file.parent() {
+                    Some(folder) => folder.to_path_buf(),
+                    None => continue,
+                };
+                if watched_folders.contains(&folder) {
+                    continue;
+                }
+                match watcher.watch(&folder, RecursiveMode::NonRecursive) {
+                    Ok(_) => {
+                        watched_folders.insert(folder);
+                    }
+                    Err(err) => warn(&format!("{} can't be watched: {}", folder.display(), err)),
+                }
+            }
+        }
+
+        if let Ok(Ok(event)) = receiver.recv_timeout(TICK) {
+            if event.paths.iter().any(|path| files.contains(path)) {
+                deadline = Some(Instant::now() + DEBOUNCE);
+            }
+        }
+    }
+
+    progress("watch", &String::from("stopped"));
+    return Ok(());
+}